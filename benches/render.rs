@@ -1,21 +1,33 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use raytracing::{
-    self, color,
+    self,
+    camera::Perspective,
+    color,
+    error::Result,
+    file::{FileWriter, ToneMap},
+    interval,
     materials::{Dielectric, Lambertian, Metal},
-    objects::{Scene, Sphere},
-    point3, vec3, Options, FOV,
+    objects::Sphere,
+    point3,
+    structs::{Color, Interval, Ray, Scene},
+    vec3, Aperture, ApertureShape, FilmBack, Options, FOV,
 };
-use std::io::Write;
 use std::sync::Arc;
 
+/// A writer that throws away everything, so only the rendering itself is measured.
 struct DummyWriter {}
 
-impl Write for DummyWriter {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        Ok(buf.len())
+impl FileWriter for DummyWriter {
+    fn begin(&mut self, _: u16, _: u16) -> Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, _: Color) -> Result<()> {
+        Ok(())
     }
 
-    fn flush(&mut self) -> std::io::Result<()> {
+    fn finish(&mut self) -> Result<()> {
         Ok(())
     }
 }
@@ -29,13 +41,13 @@ fn render() {
         scene.add(Box::new(Sphere::new(
             point3!(0, -100.5, -1),
             100,
-            Arc::new(Lambertian::new(color!(205, 205, 0))),
+            Arc::new(Lambertian::new(color!(0.8, 0.8, 0.0))),
             vec3!(0, 0, 0),
         )));
         scene.add(Box::new(Sphere::new(
             point3!(0, 0, -1),
             0.5,
-            Arc::new(Lambertian::new(color!(180, 77, 77))),
+            Arc::new(Lambertian::new(color!(0.7, 0.3, 0.3))),
             vec3!(0, 0, 0),
         )));
         scene.add(Box::new(Sphere::new(
@@ -47,7 +59,7 @@ fn render() {
         scene.add(Box::new(Sphere::new(
             point3!(1, 0, -1),
             0.5,
-            Arc::new(Metal::new(color!(204, 204, 204), 0.1)),
+            Arc::new(Metal::new(color!(0.8, 0.8, 0.8), 0.1)),
             vec3!(0, 0, 0),
         )));
 
@@ -55,27 +67,97 @@ fn render() {
     };
 
     let opts = Options {
-        scene: scene,
+        scene: Arc::new(scene),
         width: 800,
         height: 400,
-        fov: FOV::Vertical(50.0),
+        pixel_aspect_ratio: 1.0,
+        film_back: FilmBack::FULL_FRAME,
+        projection: Arc::new(Perspective::new(FOV::Vertical(50.0))),
         look_from: point3!(0.0, 0.0, 1.0),
         look_to: point3!(0.0, 0.0, 0.0),
         vup: vec3!(0.0, 1.0, 0.0),
         max_bounces: 20,
         samples: 20,
         shutter_open_duration: 1.0 / 24.0,
+        aperture: Aperture::Pinhole,
+        aperture_shape: ApertureShape::Circle,
+        focus_distance: None,
+        exposure: 0.0,
+        tone_map: ToneMap::Clamp,
+        spectral: false,
     };
 
-    raytracing::run(opts, &mut writer);
+    raytracing::run(opts, &mut writer).unwrap();
+}
+
+/// A grid of a few thousand small (and some moving) spheres, along with a batch of rays to shoot at it.
+fn many_spheres() -> (Scene, Vec<Ray>) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut scene = Scene::new();
+    let material = Arc::new(Lambertian::new(color!(0.7, 0.3, 0.3)));
+
+    for i in -30..30 {
+        for j in -30..30 {
+            let center = point3!(
+                i as f64 + 0.9 * rng.gen_range(0.0..1.0),
+                0.2,
+                j as f64 + 0.9 * rng.gen_range(0.0..1.0)
+            );
+            let velocity = match rng.gen_bool(0.2) {
+                true => vec3!(0, rng.gen_range(0.0..1.0), 0),
+                false => vec3!(0, 0, 0),
+            };
+
+            scene.add(Box::new(Sphere::new(
+                center,
+                0.2,
+                material.clone(),
+                velocity,
+            )));
+        }
+    }
+
+    let rays = (0..1000)
+        .map(|_| {
+            let target = point3!(rng.gen_range(-30.0..30.0), 0.0, rng.gen_range(-30.0..30.0));
+            let origin = point3!(0, 5, 40);
+            Ray::new(origin, target - origin)
+        })
+        .collect();
+
+    (scene, rays)
 }
 
 fn bench(c: &mut Criterion) {
     let mut group = c.benchmark_group("render spheres");
     group.sample_size(10);
-    group.bench_function("render fn", |b| b.iter(|| render()));
+    group.bench_function("render fn", |b| b.iter(render));
+    group.finish();
+}
+
+fn bench_bvh(c: &mut Criterion) {
+    let (scene, rays) = many_spheres();
+    let shutter = interval!(0.0, 1.0 / 24.0);
+    let bvh = scene.build_bvh(shutter);
+
+    let mut group = c.benchmark_group("scene hits");
+    group.bench_function("linear", |b| {
+        b.iter(|| {
+            for ray in &rays {
+                black_box(scene.does_hit(*ray, interval!(0.01, f64::INFINITY), 0.0));
+            }
+        })
+    });
+    group.bench_function("bvh", |b| {
+        b.iter(|| {
+            for ray in &rays {
+                black_box(scene.does_hit_bvh(&bvh, *ray, interval!(0.01, f64::INFINITY), 0.0));
+            }
+        })
+    });
+    group.bench_function("bvh build", |b| b.iter(|| scene.build_bvh(shutter)));
     group.finish();
 }
 
-criterion_group!(benches, bench);
+criterion_group!(benches, bench, bench_bvh);
 criterion_main!(benches);
@@ -0,0 +1,639 @@
+//! Implementation of camera (rendering the scene).
+
+use crate::{
+    error::Result,
+    file::{FileWriter, OutputTransform, PixelFormat},
+    interval,
+    lights::LightHit,
+    materials::Material,
+    structs::{
+        spectrum, Bvh, Color, HitData, Interval, Point3, Ray, SampledWavelengths, Scene, Spectrum,
+        Vec3,
+    },
+    Aperture, ApertureShape, Options,
+};
+use rand::{rngs::ThreadRng, Rng};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use std::{
+    collections::HashMap,
+    f64::consts::TAU,
+    sync::{mpsc, Arc},
+};
+
+mod projection;
+
+pub use projection::{
+    CameraSample, Equirectangular, Fisheye, FisheyeMapping, Orthographic, Perspective, Projection,
+};
+
+/// Weight for a sample from one of two ways of sampling the same thing, given the pdfs of both picking it,
+/// so that the combination favours whichever is better at finding it (multiple importance sampling).
+///
+/// https://pbr-book.org/3ed-2018/Monte_Carlo_Integration/Importance_Sampling#MultipleImportanceSampling
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+
+    match a + b > 0.0 {
+        true => a / (a + b),
+        false => 0.0,
+    }
+}
+
+/// Weight for light that a ray scattered into, given the pdf of the material scattering it that way (if the light
+/// was also sampled directly from there, see `Camera::direct_light()`) and the pdf of sampling the light directly.
+fn mis_weight(bsdf_pdf: Option<f64>, light_pdf: f64) -> f64 {
+    bsdf_pdf.map_or(1.0, |bsdf_pdf| power_heuristic(bsdf_pdf, light_pdf))
+}
+
+/// A reflectance (like a material's weight) as carried along a path, which is RGB unless rendering spectrally.
+fn reflectance(color: Color, wavelengths: Option<SampledWavelengths>) -> Color {
+    wavelengths.map_or(color, |wavelengths| wavelengths.reflectance(color))
+}
+
+/// Light given off by something, as carried along a path.
+fn radiance(spectrum: &Spectrum, wavelengths: Option<SampledWavelengths>) -> Color {
+    wavelengths.map_or_else(
+        || spectrum.color(),
+        |wavelengths| wavelengths.radiance(spectrum),
+    )
+}
+
+/// What's left of light after travelling `distance` through the inside of an object made of `medium`
+/// (or through nothing, if there isn't one), as carried along a path.
+///
+/// Light from infinitely far away can only be inside an object that isn't closed, so it's left alone.
+fn transmittance(
+    medium: Option<&Arc<dyn Material + Sync + Send>>,
+    distance: f64,
+    wavelengths: Option<SampledWavelengths>,
+) -> Color {
+    medium
+        .filter(|_| distance.is_finite())
+        .map_or(Color::WHITE, |medium| {
+            reflectance(medium.transmittance(distance), wavelengths)
+        })
+}
+
+/// The material of the object that light coming from `wi` travels through on its way to the hit point,
+/// given the ones the ray is inside (see `Camera::ray_color()`).
+fn medium_towards<'a>(
+    hit: &'a HitData,
+    wi: Vec3,
+    media: &'a [Arc<dyn Material + Sync + Send>],
+) -> Option<&'a Arc<dyn Material + Sync + Send>> {
+    match (wi.dot(hit.normal()) > 0.0, hit.is_front_face()) {
+        // The side the ray came from.
+        (true, _) => media.last(),
+        // Through the surface, into the object.
+        (false, true) => Some(&hit.material),
+        // Through the surface, out of the object and back into whatever is around it.
+        (false, false) => media.split_last().and_then(|(_, around)| around.last()),
+    }
+}
+
+/// Struct representing a camera.
+///
+/// A camera owns everything it needs to render (the scene and all the settings from `Options`),
+/// so it can be rendered any number of times, and separate cameras can render concurrently.
+/// See constructor for what the fields are.
+#[derive(Debug)]
+pub struct Camera {
+    /// The scene itself.
+    scene: Arc<Scene>,
+    /// Hierarchy over the scene's objects, so every ray doesn't have to check every object.
+    bvh: Bvh,
+    /// Width and height of the rendered image.
+    width: u16,
+    height: u16,
+    /// Max. no of bounces a ray can have before it just turns black.
+    max_bounces: u8,
+    /// Max. no of samples. More samples give a more "smooth" look but are more compute-intensive.
+    samples: u16,
+    /// The duration the camera's shutter is open (for motion blur).
+    shutter_open_duration: f64,
+    /// How points on the film turn into rays.
+    projection: Arc<dyn Projection + Sync + Send>,
+    /// The camera's assumed center (the center of the lens).
+    look_from: Point3,
+    /// The camera's right, up and backwards directions (see constructor).
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    /// The size of the part of the film being rendered (width, height), in meters.
+    film_size: (f64, f64),
+    focus_distance: f64,
+    /// Zero for a pinhole camera.
+    lens_radius: f64,
+    aperture_shape: ApertureShape,
+    /// Applied to every pixel before it's written.
+    output_transform: OutputTransform,
+    /// Whether paths carry wavelengths instead of RGB.
+    spectral: bool,
+}
+
+impl Camera {
+    /// The main render function that does frankly everything.
+    ///
+    /// Fails if the writer fails, in which case the file is likely incomplete.
+    pub fn render(&self, file_writer: &mut dyn FileWriter) -> Result<()> {
+        // A receiver and (about to be cloned a lot) sender to send back the results of each compute.
+        let (sender, receiver) = mpsc::channel::<(u16, Arc<Vec<Color>>)>();
+
+        // Before computing anything, so writers can time the render (and a writer that can't take the image
+        // fails straight away).
+        file_writer.begin(self.width, self.height)?;
+
+        #[cfg(debug_assertions)]
+        println!("Starting computing.");
+
+        // Loop through every row of the image.
+        (0..self.height)
+            .into_par_iter()
+            .for_each_with(sender, |s, i| {
+                //? #[cfg(debug_assertions)]
+                //? println!("Computing row {}", i);
+
+                // The entire row stored as a vector of color.
+                let mut pixels = vec![];
+
+                let mut rng = rand::thread_rng();
+
+                // For every pixel..
+                for j in 0..self.width {
+                    let mut color = Color::BLACK;
+
+                    // ..go through every sample ray
+                    for _ in 0..self.samples {
+                        // get the color (or nothing, for parts of the film the projection doesn't cover)
+                        if let Some(ray) = self.get_ray(i, j, &mut rng) {
+                            // add it to the `color` variable
+                            color += self
+                                .ray_color(ray, rng.gen_range(0.0..=self.shutter_open_duration));
+                        }
+                    }
+
+                    // and just average it over the number of samples.
+                    pixels.push(color / self.samples);
+                }
+
+                // and send them.
+                s.send((i, Arc::new(pixels))).unwrap();
+            });
+
+        // The following code receives the completed rows from each thread and writes to file.
+
+        // The row we are waiting on
+        let mut current_pending_row: u16 = 0;
+
+        // Since the rows will come in out-of-order (some will complete before others), store them here
+        // if its not their turn.
+        let mut row_hashes: HashMap<u16, Arc<Vec<Color>>> = HashMap::new();
+
+        #[cfg(debug_assertions)]
+        println!("Starting writing");
+
+        while current_pending_row < self.height {
+            //? #[cfg(debug_assertions)]
+            //? println!("Waiting for row {}", current_pending_row);
+
+            match receiver.try_recv() {
+                Ok((i, row)) => {
+                    // Check if the received row is the one we need.
+                    if i == current_pending_row {
+                        // Write it to the file
+                        self.write_row(row, file_writer)?;
+                        current_pending_row += 1;
+                    } else {
+                        // Put it in the waiting list
+                        row_hashes.insert(i, row);
+                    }
+                }
+                Err(_) => {
+                    // Check if the row we need is now on the hashmap
+                    if row_hashes.contains_key(&current_pending_row) {
+                        // Remove the row from the hashmap, since its turn has arrived..
+                        let row = row_hashes.remove(&current_pending_row).unwrap();
+
+                        // ..and write it.
+                        self.write_row(row, file_writer)?;
+                        current_pending_row += 1;
+                    }
+                }
+            };
+        }
+
+        file_writer.finish()
+    }
+
+    /// Function to write a vector of colors to the file.
+    ///
+    /// Unless the writer wants the raw radiance, they are run through the output transform first.
+    fn write_row(&self, row: Arc<Vec<Color>>, file_writer: &mut dyn FileWriter) -> Result<()> {
+        let pixel_format = file_writer.pixel_format();
+
+        for pixel in row.iter() {
+            let color = match pixel_format {
+                PixelFormat::Display => self.output_transform.apply(*pixel),
+                PixelFormat::Linear => *pixel,
+            };
+
+            file_writer.write(color)?;
+        }
+
+        Ok(())
+    }
+
+    /// Function to get the ray corresponding to the particular pixel.
+    ///
+    /// Each time its called (which should be equal to the no. of samples),
+    /// it will randomize by a bit, both within the pixel and on the lens.
+    ///
+    /// The projection decides the ray in the camera's own frame, which is then moved to
+    /// where the camera is and turned to where it's looking.
+    fn get_ray(&self, i: u16, j: u16, rng: &mut ThreadRng) -> Option<Ray> {
+        // A random point within the pixel, in film coordinates.
+        // The image files are written from the top row to the bottom, so the film is walked downwards.
+        let x = (j as f64 + rng.gen_range(0.0..1.0)) / self.width as f64;
+        let y = (i as f64 + rng.gen_range(0.0..1.0)) / self.height as f64;
+
+        let (lens_u, lens_v) = self.lens_sample(rng);
+
+        let ray = self.projection.ray(CameraSample {
+            film: (x * 2.0 - 1.0, 1.0 - y * 2.0),
+            film_size: self.film_size,
+            lens: (lens_u * self.lens_radius, lens_v * self.lens_radius),
+            focus_distance: self.focus_distance,
+        })?;
+
+        let to_world = |p: Vec3| (self.u * p.x()) + (self.v * p.y()) + (self.w * p.z());
+
+        Some(Ray::new(
+            self.look_from + to_world(ray.origin()),
+            to_world(ray.direction()),
+        ))
+    }
+
+    /// Generate a random point on the lens' opening (of radius 1), in the lens' right/up coordinates.
+    fn lens_sample(&self, rng: &mut ThreadRng) -> (f64, f64) {
+        match self.aperture_shape {
+            // Fewer than 3 blades can't make a polygon, so they're just treated as a circle.
+            ApertureShape::Polygon { blades, rotation } if blades >= 3 => {
+                // Pick one of the triangles between the center and the polygon's edges..
+                let blade = rng.gen_range(0..blades) as f64;
+                let angle = rotation.to_radians() + blade * TAU / blades as f64;
+                let next_angle = angle + TAU / blades as f64;
+
+                // ..and a uniformly random point in it, folding points outside the triangle back in.
+                let (mut a, mut b) = (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+                if a + b > 1.0 {
+                    (a, b) = (1.0 - a, 1.0 - b);
+                }
+
+                (
+                    a * angle.cos() + b * next_angle.cos(),
+                    a * angle.sin() + b * next_angle.sin(),
+                )
+            }
+            _ => loop {
+                // Generate points in the square around the circle until one is inside it.
+                let (x, y) = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+
+                if x * x + y * y < 1.0 {
+                    break (x, y);
+                }
+            },
+        }
+    }
+
+    /// Function that takes a ray, follows it as it bounces around the scene, and returns the appropriate color to display.
+    ///
+    /// At every surface that scatters light all over, the lights and the environment are also sampled directly
+    /// (see `direct_light()`), and light the ray runs into afterwards is weighted so nothing is counted twice
+    /// (multiple importance sampling).
+    fn ray_color(&self, mut ray: Ray, time: f64) -> Color {
+        let mut color = Color::BLACK;
+        // How much of the light found from here on makes it back to the camera, after all the bounces so far.
+        let mut throughput = Color::WHITE;
+        // The pdf of the last material scattering the ray the way it's going, if the lights were also sampled there.
+        let mut bsdf_pdf: Option<f64> = None;
+        // The single wavelength the ray carries, once it's hit something that splits light up by wavelength.
+        let mut wavelength: Option<f64> = None;
+        // When rendering spectrally, the wavelengths the path carries (one per channel of the colors above).
+        let wavelengths = match self.spectral {
+            true => Some(SampledWavelengths::sample(
+                rand::thread_rng().gen_range(0.0..1.0),
+            )),
+            false => None,
+        };
+        // The materials of the objects the ray went into (through their surface) and hasn't left yet,
+        // the last one being what it's travelling through right now.
+        let mut media: Vec<Arc<dyn Material + Sync + Send>> = Vec::new();
+
+        // Follow the ray until it bounces a certain no. of times (after which it just turns black),
+        // is absorbed, or goes off into the 'sky'.
+        for bounce in 0..self.max_bounces {
+            // Check if the scene has any object that is in the path of this ray.
+            //
+            // The interval starts at 0.1 to prevent shadow-acne (see below), where if a ray
+            // just bounced off a surface, the same surface might appear to be in the path again.
+            //
+            // PS: https://stackoverflow.com/questions/36908835/what-causes-shadow-acne
+            let hit = self
+                .scene
+                .does_hit_bvh(&self.bvh, ray, interval!(0.01, f64::INFINITY), time);
+
+            // Lights aren't part of the scene's objects, so check if the ray runs into one before anything else.
+            let end = hit.as_ref().map_or(f64::INFINITY, |hit| *hit.time());
+            let light = self.light_hit(ray, interval!(0.01, end));
+
+            // Lights that have a size block whatever is behind them.
+            if let Some(light) = light.as_ref().filter(|light| light.time.is_finite()) {
+                color += throughput
+                    * transmittance(
+                        media.last(),
+                        light.time * ray.direction().length(),
+                        wavelengths,
+                    )
+                    * radiance(&light.radiance, wavelengths)
+                    * mis_weight(bsdf_pdf, light.pdf);
+                break;
+            }
+
+            // If the ray doesn't hit anything, it sees the scene's environment (the sky, by default)
+            // and any directional lights it looks straight into,
+            // unless it's hidden and this ray came straight from the camera.
+            let Some(hit) = hit else {
+                if bounce > 0 || self.scene.environment_visible() {
+                    let environment = self.scene.environment();
+                    let environment_pdf = environment.pdf(ray.direction());
+
+                    // The environment and the lights were already sampled directly at the last hit,
+                    // so only count the part of their light that the material is better at finding.
+                    let sun = light.map_or(Color::BLACK, |light| {
+                        radiance(&light.radiance, wavelengths) * mis_weight(bsdf_pdf, light.pdf)
+                    });
+                    let sky = Spectrum::from(environment.color(ray.direction()));
+
+                    color += throughput
+                        * (radiance(&sky, wavelengths) * mis_weight(bsdf_pdf, environment_pdf)
+                            + sun);
+                }
+
+                break;
+            };
+
+            // Materials that bend each wavelength differently (like prisms) send them off in different directions,
+            // so the first time one is hit, only one wavelength can be followed from then on.
+            if wavelength.is_none() && hit.material.is_dispersive() {
+                match wavelengths {
+                    // When rendering spectrally, that's the first of the path's wavelengths (hero wavelength
+                    // sampling), and the others are dropped here, with it standing in for them
+                    // (it's as good a guess as them, so it counts for all 3).
+                    Some(wavelengths) => {
+                        throughput = Color::new(throughput.r() * 3.0, 0.0, 0.0);
+                        wavelength = Some(wavelengths.hero());
+                    }
+                    // In RGB, there are no wavelengths to pick from, so a single one is picked at random,
+                    // keeping only its part of the light (its color, over the odds of picking it).
+                    // This is noisier than rendering spectrally, as every path through a prism only sees one color.
+                    None => {
+                        let sampled =
+                            spectrum::sample_wavelength(rand::thread_rng().gen_range(0.0..1.0));
+
+                        throughput = throughput * spectrum::wavelength_weight(sampled);
+                        wavelength = Some(sampled);
+                    }
+                }
+            }
+
+            let hit = match wavelength {
+                Some(wavelength) => hit.with_wavelength(wavelength),
+                None => hit,
+            };
+
+            // Some of the light is absorbed on the way, if the ray travelled through something (like tinted glass).
+            let distance = *hit.time() * ray.direction().length();
+            throughput = throughput * transmittance(media.last(), distance, wavelengths);
+
+            // Light given off by the surface itself (black for most materials).
+            color += throughput * radiance(&hit.material.emitted(&hit), wavelengths);
+
+            // The direction back along the ray, towards where it came from.
+            let wo = -ray.direction().unit_vec();
+
+            // Materials that aren't entirely specular (unlike mirrors and glass) can also be lit by looking
+            // for the lights and the environment directly, which finds small bright ones way faster.
+            // This has to happen before scattering, as the light is found even if the scattered ray is absorbed.
+            if hit.material.has_non_specular_lobe() {
+                color += throughput * self.direct_light(&hit, wo, time, &media, wavelengths);
+            }
+
+            // Call the scatter function on the material of the surface just hit.
+            // Nothing is scattered (eg. off a light), so there's no point following the ray further.
+            let Some(scatter) = hit.material.scatter(&hit, ray) else {
+                break;
+            };
+
+            // Light the scattered ray runs into is weighted against the lights sampled above,
+            // unless it's off a specular lobe, which sampling the lights can never find.
+            bsdf_pdf = match scatter.lobe.is_specular() {
+                true => None,
+                false => Some(scatter.pdf),
+            };
+
+            // For every bounce off a surface, whatever is found further along is multiplied by the weight
+            // (like the `albedo`) of the material.
+            throughput = throughput * reflectance(scatter.weight, wavelengths);
+            ray = scatter.ray;
+
+            // Going through the surface means going into the object, or out of it through the back.
+            if scatter.lobe.transmission {
+                match hit.is_front_face() {
+                    true => media.push(hit.material.clone()),
+                    false => {
+                        media.pop();
+                    }
+                }
+            }
+        }
+
+        // The film only takes RGB.
+        wavelengths.map_or(color, |wavelengths| wavelengths.to_color(color))
+    }
+
+    /// The closest light (see `Scene::add_light()`) the ray runs into within the interval.
+    fn light_hit(&self, ray: Ray, interval: Interval) -> Option<LightHit> {
+        self.scene
+            .lights()
+            .iter()
+            .filter_map(|light| light.hit(ray, interval))
+            .min_by(|a, b| a.time.total_cmp(&b.time))
+    }
+
+    /// Check if anything is in the way of the ray before `distance`.
+    fn is_shadowed(&self, ray: Ray, distance: f64, time: f64) -> bool {
+        self.scene
+            .does_hit_bvh(&self.bvh, ray, interval!(0.01, distance), time)
+            .is_some()
+    }
+
+    /// Light from the lights and the environment reaching the hit point, found by picking a direction towards
+    /// each of them (more likely where they're brighter) and checking if anything is in the way.
+    ///
+    /// `wo` is the direction towards where the ray came from, `media` the materials of the objects the ray is inside
+    /// (see `ray_color()`), and `wavelengths` the ones carried by the path, if rendering spectrally.
+    fn direct_light(
+        &self,
+        hit: &HitData,
+        wo: Vec3,
+        time: f64,
+        media: &[Arc<dyn Material + Sync + Send>],
+        wavelengths: Option<SampledWavelengths>,
+    ) -> Color {
+        let mut color = Color::BLACK;
+
+        for light in self.scene.lights() {
+            let Some(sample) = light.sample(hit.point()) else {
+                continue;
+            };
+
+            let scattered = hit.material.eval(hit, wo, sample.direction);
+            if scattered.is_black()
+                || self.is_shadowed(
+                    Ray::new(hit.point(), sample.direction),
+                    sample.distance,
+                    time,
+                )
+            {
+                continue;
+            }
+
+            // Lights without a size can only be found this way, so they count in full.
+            let weight = match sample.pdf {
+                Some(light_pdf) => {
+                    power_heuristic(light_pdf, hit.material.pdf(hit, wo, sample.direction))
+                        / light_pdf
+                }
+                None => 1.0,
+            };
+
+            color += reflectance(scattered, wavelengths)
+                * transmittance(
+                    medium_towards(hit, sample.direction, media),
+                    sample.distance,
+                    wavelengths,
+                )
+                * radiance(&sample.radiance, wavelengths)
+                * weight;
+        }
+
+        let environment = self.scene.environment();
+
+        if let Some((wi, light_pdf)) = environment.sample() {
+            let scattered = hit.material.eval(hit, wo, wi);
+
+            if !scattered.is_black()
+                && light_pdf > 0.0
+                && !self.is_shadowed(Ray::new(hit.point(), wi), f64::INFINITY, time)
+            {
+                // The material could've found this light by scattering too (see `ray_color()`),
+                // so only count the part of it that sampling the environment is better at finding.
+                let weight = power_heuristic(light_pdf, hit.material.pdf(hit, wo, wi));
+
+                let sky = Spectrum::from(environment.color(wi));
+
+                color += reflectance(scattered, wavelengths)
+                    * radiance(&sky, wavelengths)
+                    * (weight / light_pdf);
+            }
+        }
+
+        color
+    }
+
+    /// Constructor for a camera, taking all the settings from the passed `Options`.
+    pub fn new(opts: &Options) -> Self {
+        // Variables used repeatedly in this function.
+        let look_from = opts.look_from;
+        let look_to = opts.look_to;
+        let vup = opts.vup;
+
+        // Calculate the focus distance.
+        // Unless it's set explicitly, it's possible to get it as `look_to` is a point and not a direction
+        // (which it normally should be), so things at `look_to` are in focus.
+        let focus_distance = opts
+            .focus_distance
+            .unwrap_or_else(|| (look_from - look_to).length());
+
+        // The shape of the image, as it will be displayed (with the pixels stretched to their aspect ratio).
+        let aspect = (opts.width as f64 * opts.pixel_aspect_ratio) / opts.height as f64;
+
+        // The largest part of the film with that shape, in meters.
+        let film_back = (
+            opts.film_back.width / 1000.0,
+            opts.film_back.height / 1000.0,
+        );
+        let film_size = match film_back.0 / film_back.1 > aspect {
+            // The film is wider than the image, so its sides are cropped..
+            true => (film_back.1 * aspect, film_back.1),
+            // ..or it's taller, so its top and bottom are.
+            false => (film_back.0, film_back.0 / aspect),
+        };
+
+        // The basis unit vectors to describe the camera's orientation.
+        //
+        // `v` is the vector pointing to camera's upwards.
+        // (positive y-axis from camera's frame of reference, if `look_to` lies on the negative z-axis)
+        //
+        // `u` is the vector pointing to camera's right.
+        // (positive x-axis in the above analogy)
+        //
+        // `w` is the vector pointing away from the direction the camera is looking.
+        // (positive z-axis in above analogy)
+        //
+        // `v` really does point up. Before `Vec3::cross()` was fixed it pointed down for level cameras,
+        // and the image was built upwards from its bottom row, so the two mistakes cancelled out
+        // (and tilted cameras got a skewed basis). Now the first row is the top of the film (see `get_ray()`),
+        // matching the order images are written in.
+        let w = (look_from - look_to).unit_vec();
+        let u = vup.cross(w).unit_vec();
+        let v = w.cross(u);
+
+        // The radius of the lens.
+        let lens_radius = match opts.aperture {
+            Aperture::Pinhole => 0.0,
+            Aperture::DefocusAngle(angle) => focus_distance * (angle.to_radians() / 2.0).tan(),
+            // Projections without a focal length can't be given an f-stop, so they're left as a pinhole.
+            Aperture::FStop(f_number) => opts
+                .projection
+                .focal_length(film_size)
+                .map_or(0.0, |focal_length| focal_length / f_number / 2.0),
+        };
+
+        // Objects can move while the shutter is open, so the bounds have to cover that entire duration.
+        let bvh = opts
+            .scene
+            .build_bvh(interval!(0.0, opts.shutter_open_duration));
+
+        Camera {
+            scene: Arc::clone(&opts.scene),
+            bvh,
+            width: opts.width,
+            height: opts.height,
+            max_bounces: opts.max_bounces,
+            samples: opts.samples,
+            shutter_open_duration: opts.shutter_open_duration,
+            projection: Arc::clone(&opts.projection),
+            look_from,
+            u,
+            v,
+            w,
+            film_size,
+            focus_distance,
+            lens_radius,
+            aperture_shape: opts.aperture_shape,
+            output_transform: OutputTransform::new(opts.exposure, opts.tone_map),
+            spectral: opts.spectral,
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! The different ways a camera can map its film to rays in the scene.
+
+use crate::{point3, structs::Ray, vec3, FOV};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// A point the camera wants a ray for, in the camera's own frame of reference.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraSample {
+    /// The point on the film, both going from -1 to 1 (left to right, and bottom to top).
+    pub film: (f64, f64),
+    /// The physical size (width, height) of the part of the film that's being rendered, in meters.
+    pub film_size: (f64, f64),
+    /// The point on the lens' opening, already scaled to the lens' radius.
+    pub lens: (f64, f64),
+    /// Distance from the lens to where things are perfectly in focus.
+    pub focus_distance: f64,
+}
+
+impl CameraSample {
+    /// The film's width divided by its height.
+    pub fn aspect(&self) -> f64 {
+        self.film_size.0 / self.film_size.1
+    }
+}
+
+/// A trait defining a camera's projection, ie. which ray each point of the film sees.
+///
+/// Rays are generated in the camera's frame of reference, with the lens at the origin,
+/// x going right, y going up, and the camera looking down the negative z-axis.
+/// The camera takes care of moving them to wherever it is in the scene.
+pub trait Projection: std::fmt::Debug {
+    /// The ray for a point of the film, or `None` if the projection doesn't cover that point
+    /// (eg. outside a fisheye's image circle), in which case it's just black.
+    fn ray(&self, sample: CameraSample) -> Option<Ray>;
+
+    /// The focal length of a lens with this projection, for a film of `film_size` (width, height),
+    /// in the same units.
+    ///
+    /// Used for turning an f-stop into a lens size, so projections without a focal length
+    /// (the default) can only have a lens of a set size or none at all.
+    fn focal_length(&self, _film_size: (f64, f64)) -> Option<f64> {
+        None
+    }
+}
+
+/// The usual projection of a pinhole camera (or a thin lens), where straight lines stay straight.
+#[derive(Debug, Clone, Copy)]
+pub struct Perspective {
+    fov: FOV,
+}
+
+impl Perspective {
+    pub fn new(fov: FOV) -> Self {
+        Perspective { fov }
+    }
+
+    /// Half the height of the film, if it was placed at a distance of 1 from the lens.
+    fn half_height(&self, (film_width, film_height): (f64, f64)) -> f64 {
+        match self.fov {
+            FOV::Vertical(fov) => (fov.to_radians() / 2.0).tan(),
+            // The film's width and height at that distance have the same ratio as the film itself.
+            FOV::Horizontal(fov) => (fov.to_radians() / 2.0).tan() * film_height / film_width,
+            // By similar triangles, half the film over the focal length.
+            FOV::FocalLength(focal_length) => (film_height * 1000.0 / 2.0) / focal_length,
+        }
+    }
+}
+
+impl Projection for Perspective {
+    fn ray(&self, sample: CameraSample) -> Option<Ray> {
+        let half_height = self.half_height(sample.film_size);
+        let (x, y) = sample.film;
+
+        // The film, projected out onto the focus plane.
+        let target =
+            vec3!(x * half_height * sample.aspect(), y * half_height, -1) * sample.focus_distance;
+        let origin = point3!(sample.lens.0, sample.lens.1, 0);
+
+        Some(Ray::new(origin, target - origin))
+    }
+
+    fn focal_length(&self, film_size: (f64, f64)) -> Option<f64> {
+        Some((film_size.1 / 2.0) / self.half_height(film_size))
+    }
+}
+
+/// A projection where all rays are parallel, so things don't get smaller with distance
+/// (as in technical drawings).
+#[derive(Debug, Clone, Copy)]
+pub struct Orthographic {
+    /// The height of the view, in the scene's units.
+    height: f64,
+}
+
+impl Orthographic {
+    pub fn new<H: Into<f64>>(height: H) -> Self {
+        Orthographic {
+            height: height.into(),
+        }
+    }
+}
+
+impl Projection for Orthographic {
+    fn ray(&self, sample: CameraSample) -> Option<Ray> {
+        let (x, y) = sample.film;
+        let film_point = point3!(
+            x * self.height / 2.0 * sample.aspect(),
+            y * self.height / 2.0,
+            0
+        );
+
+        // With a lens, the rays still converge on the same point of the focus plane.
+        let origin = film_point + vec3!(sample.lens.0, sample.lens.1, 0);
+        let target = film_point + vec3!(0, 0, -sample.focus_distance);
+
+        Some(Ray::new(origin, target - origin))
+    }
+}
+
+/// How a fisheye lens maps the angle away from the view direction to the distance from the image's center.
+#[derive(Debug, Clone, Copy)]
+pub enum FisheyeMapping {
+    /// The distance is proportional to the angle, so angles are preserved.
+    Equidistant,
+    /// Every part of the image covers the same solid angle, so areas are preserved.
+    Equisolid,
+}
+
+/// A fisheye lens, which fits a really wide field-of-view into a circle that spans the image's height.
+///
+/// Ignores the camera's lens, so everything is always in focus.
+#[derive(Debug, Clone, Copy)]
+pub struct Fisheye {
+    /// The field-of-view across the image circle, in degrees.
+    /// Can go up to 360° for `Equidistant`, and 360° is (impractically) everything for `Equisolid`.
+    fov: f64,
+    mapping: FisheyeMapping,
+}
+
+impl Fisheye {
+    pub fn new(fov: f64, mapping: FisheyeMapping) -> Self {
+        Fisheye { fov, mapping }
+    }
+}
+
+impl Projection for Fisheye {
+    fn ray(&self, sample: CameraSample) -> Option<Ray> {
+        let (x, y) = (sample.film.0 * sample.aspect(), sample.film.1);
+
+        // The distance from the center, where the image circle's edge is 1.
+        let radius = (x * x + y * y).sqrt();
+        if radius > 1.0 {
+            return None;
+        }
+
+        let max_angle = self.fov.to_radians() / 2.0;
+
+        // The angle between the ray and the view direction.
+        let theta = match self.mapping {
+            FisheyeMapping::Equidistant => radius * max_angle,
+            FisheyeMapping::Equisolid => {
+                2.0 * (radius * (max_angle / 2.0).sin()).clamp(-1.0, 1.0).asin()
+            }
+        };
+        // And the angle around the view direction.
+        let phi = y.atan2(x);
+
+        let direction = vec3!(
+            theta.sin() * phi.cos(),
+            theta.sin() * phi.sin(),
+            -theta.cos()
+        );
+
+        Some(Ray::new(point3!(0, 0, 0), direction))
+    }
+}
+
+/// A 360° panorama, with longitude going across the image and latitude going up it.
+///
+/// The center of the image is the view direction. The whole sphere fits a 2:1 image,
+/// which is the format used for environment maps and VR.
+///
+/// Ignores the camera's lens, so everything is always in focus.
+#[derive(Debug, Clone, Copy)]
+pub struct Equirectangular;
+
+impl Projection for Equirectangular {
+    fn ray(&self, sample: CameraSample) -> Option<Ray> {
+        let longitude = sample.film.0 * PI;
+        let latitude = sample.film.1 * FRAC_PI_2;
+
+        let direction = vec3!(
+            latitude.cos() * longitude.sin(),
+            latitude.sin(),
+            -latitude.cos() * longitude.cos()
+        );
+
+        Some(Ray::new(point3!(0, 0, 0), direction))
+    }
+}
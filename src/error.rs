@@ -0,0 +1,59 @@
+//! The error type for everything in the crate that can fail.
+//!
+//! Importers return the more detailed `ImportError`, which converts into it, so `?` works on both.
+
+use crate::import::ImportError;
+use std::{
+    error,
+    fmt::{self, Display},
+    io,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// The file format (or something asked of it) isn't supported.
+    UnsupportedFormat(String),
+    /// A different no. of pixels was written than the image's dimensions allow.
+    DimensionMismatch { expected: usize, got: usize },
+    /// Loading a scene file (or an image for one) failed.
+    Import(ImportError),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<ImportError> for Error {
+    fn from(error: ImportError) -> Self {
+        Error::Import(error)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::UnsupportedFormat(format) => write!(f, "Unsupported format: {}", format),
+            Error::DimensionMismatch { expected, got } => {
+                write!(f, "Expected {} pixels, got {}", expected, got)
+            }
+            Error::Import(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Import(error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use super::{check_pixel_count, FileWriter, PixelFormat};
+use crate::{
+    error::{Error, Result},
+    structs::Color,
+};
+use exr::prelude::{
+    f16, Blocks, Compression, Encoding, Image, Layer, LayerAttributes, LineOrder, SpecificChannels,
+    Vec2, WritableImage,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+/// Precision of the channels in an OpenEXR file.
+#[derive(Debug, Clone, Copy)]
+pub enum ExrPrecision {
+    /// 16-bit floats, which is plenty for color and half the size.
+    Half,
+    /// 32-bit floats.
+    Float,
+}
+
+/// Compression of the scanlines in an OpenEXR file.
+#[derive(Debug, Clone, Copy)]
+pub enum ExrCompression {
+    None,
+    /// Lossless zlib compression, in blocks of 16 scanlines.
+    Zip,
+}
+
+/// An OpenEXR image, storing the unclamped linear radiance.
+///
+/// Like PNGs, the pixels are collected in memory and the file is written in `finish()`.
+pub struct ExrFile {
+    file: BufWriter<File>,
+    width: u16,
+    height: u16,
+    precision: ExrPrecision,
+    compression: ExrCompression,
+    pixels: Vec<Color>,
+}
+
+impl From<exr::error::Error> for Error {
+    fn from(error: exr::error::Error) -> Self {
+        match error {
+            exr::error::Error::Io(error) => Error::Io(error),
+            error => Error::UnsupportedFormat(error.to_string()),
+        }
+    }
+}
+
+impl ExrFile {
+    /// A half-float, ZIP compressed EXR, see the other methods for changing that.
+    pub fn new(file: &str) -> Result<Self> {
+        let file = File::create(file)?;
+
+        Ok(ExrFile {
+            file: BufWriter::new(file),
+            width: 0,
+            height: 0,
+            precision: ExrPrecision::Half,
+            compression: ExrCompression::Zip,
+            pixels: vec![],
+        })
+    }
+
+    pub fn precision(mut self, precision: ExrPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn compression(mut self, compression: ExrCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+}
+
+impl FileWriter for ExrFile {
+    fn begin(&mut self, width: u16, height: u16) -> Result<()> {
+        self.width = width;
+        self.height = height;
+        self.pixels = Vec::with_capacity(width as usize * height as usize);
+
+        Ok(())
+    }
+
+    fn write(&mut self, color: Color) -> Result<()> {
+        self.pixels.push(color);
+        check_pixel_count(self.width, self.height, self.pixels.len(), false)
+    }
+
+    /// Write the collected pixels to the file.
+    fn finish(&mut self) -> Result<()> {
+        check_pixel_count(self.width, self.height, self.pixels.len(), true)?;
+
+        let encoding = Encoding {
+            compression: match self.compression {
+                ExrCompression::None => Compression::Uncompressed,
+                ExrCompression::Zip => Compression::ZIP16,
+            },
+            blocks: Blocks::ScanLines,
+            line_order: LineOrder::Increasing,
+        };
+
+        let size = (self.width as usize, self.height as usize);
+        let pixel = |Vec2(x, y): Vec2<usize>| self.pixels[y * self.width as usize + x];
+
+        match self.precision {
+            ExrPrecision::Half => Image::from_layer(Layer::new(
+                size,
+                LayerAttributes::default(),
+                encoding,
+                SpecificChannels::rgb(|position| {
+                    let color = pixel(position);
+                    (
+                        f16::from_f64(color.r()),
+                        f16::from_f64(color.g()),
+                        f16::from_f64(color.b()),
+                    )
+                }),
+            ))
+            .write()
+            .to_buffered(&mut self.file)?,
+            ExrPrecision::Float => Image::from_layer(Layer::new(
+                size,
+                LayerAttributes::default(),
+                encoding,
+                SpecificChannels::rgb(|position| {
+                    let color = pixel(position);
+                    (color.r() as f32, color.g() as f32, color.b() as f32)
+                }),
+            ))
+            .write()
+            .to_buffered(&mut self.file)?,
+        };
+
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        PixelFormat::Linear
+    }
+}
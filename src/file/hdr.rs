@@ -0,0 +1,151 @@
+use super::{check_pixel_count, FileWriter, PixelFormat};
+use crate::{error::Result, structs::Color};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+/// A Radiance HDR (`.hdr`) image, storing the unclamped linear radiance.
+///
+/// Every pixel is stored as RGBE: an 8-bit mantissa per channel, sharing one 8-bit exponent.
+/// Scanlines are written uncompressed, which every reader supports.
+pub struct HdrFile {
+    file: BufWriter<File>,
+    width: u16,
+    height: u16,
+    written: usize,
+}
+
+impl HdrFile {
+    pub fn new(file: &str) -> Result<Self> {
+        let file = File::create(file)?;
+
+        Ok(HdrFile {
+            file: BufWriter::new(file),
+            width: 0,
+            height: 0,
+            written: 0,
+        })
+    }
+
+    /// Encode a color as RGBE.
+    fn rgbe(color: Color) -> [u8; 4] {
+        let max = color.r().max(color.g()).max(color.b());
+
+        if max < 1e-32 {
+            return [0, 0, 0, 0];
+        }
+
+        // The exponent is that of the brightest channel, such that its mantissa is in [0.5, 1).
+        let exponent = max.log2().floor() as i32 + 1;
+        let scale = 256.0 / 2.0_f64.powi(exponent);
+
+        let channel = |c: f64| (c.max(0.0) * scale).min(255.0) as u8;
+
+        [
+            channel(color.r()),
+            channel(color.g()),
+            channel(color.b()),
+            (exponent + 128).clamp(0, 255) as u8,
+        ]
+    }
+}
+
+impl FileWriter for HdrFile {
+    fn begin(&mut self, width: u16, height: u16) -> Result<()> {
+        self.width = width;
+        self.height = height;
+
+        writeln!(self.file, "#?RADIANCE")?;
+        writeln!(self.file, "FORMAT=32-bit_rle_rgbe")?;
+        writeln!(self.file)?;
+        // Rows go from top to bottom, pixels in a row go from left to right.
+        writeln!(self.file, "-Y {} +X {}", height, width)?;
+
+        Ok(())
+    }
+
+    fn write(&mut self, color: Color) -> Result<()> {
+        self.written += 1;
+        check_pixel_count(self.width, self.height, self.written, false)?;
+
+        self.file.write_all(&Self::rgbe(color))?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        check_pixel_count(self.width, self.height, self.written, true)?;
+
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        PixelFormat::Linear
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::temp_path, *};
+    use crate::import::load_image;
+
+    #[test]
+    fn rgbe_keeps_the_brightest_channel_precise() {
+        for color in [
+            Color::new(1, 0.5, 0.25),
+            Color::new(0.001, 0.002, 0.003),
+            Color::new(1e4, 1, 0),
+            Color::new(255.9, 17, 3),
+        ] {
+            let [r, g, b, exponent] = HdrFile::rgbe(color);
+
+            // The brightest channel uses the top half of the mantissa's range.
+            assert!(r.max(g).max(b) >= 128, "{:?}", color);
+            assert!(exponent > 0);
+        }
+
+        assert_eq!(HdrFile::rgbe(Color::BLACK), [0, 0, 0, 0]);
+        assert_eq!(HdrFile::rgbe(Color::new(-1, 0, 0)), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn round_trips_through_the_image_loader() {
+        let pixels = [
+            Color::new(0, 0, 0),
+            Color::new(0.25, 0.5, 1),
+            Color::new(1000, 200, 3.5),
+            Color::new(0.001, 0.0005, 0.002),
+            Color::new(7, 8, 9),
+            Color::new(10, 1, 0.1),
+        ];
+        let path = temp_path("round-trip.hdr");
+        let mut file = HdrFile::new(&path).unwrap();
+
+        file.begin(3, 2).unwrap();
+        for &color in &pixels {
+            file.write(color).unwrap();
+        }
+        file.finish().unwrap();
+
+        let image = load_image(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!((image.width(), image.height()), (3, 2));
+
+        // Every channel is within a step of the mantissa (out of 256, for the shared exponent) of the original.
+        for (color, loaded) in pixels.iter().zip(image.pixels()) {
+            let max = color.r().max(color.g()).max(color.b());
+
+            for (original, loaded) in [
+                (color.r(), loaded.r()),
+                (color.g(), loaded.g()),
+                (color.b(), loaded.b()),
+            ] {
+                assert!((original - loaded).abs() <= max / 128.0, "{:?}", color);
+            }
+        }
+    }
+}
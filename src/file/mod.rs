@@ -1,11 +1,69 @@
 //! Abstractions for interacting with image files.
 
-use crate::structs::Color;
+use crate::{
+    error::{Error, Result},
+    structs::Color,
+};
 
+/// What kind of colors a `FileWriter` wants to be passed.
+#[derive(Debug, Clone, Copy)]
+pub enum PixelFormat {
+    /// Colors that went through the `OutputTransform`, so they are in 0 - 1 and sRGB encoded.
+    Display,
+    /// The raw linear radiance from the renderer, before any exposure or tone mapping.
+    /// This is what floating point formats (for compositing etc.) want.
+    Linear,
+}
+
+/// Something the rendered image can be written to.
+///
+/// The renderer calls `begin()` once (before it starts rendering), then `write()` for every pixel
+/// (left to right, top to bottom), and then `finish()` once, after which the file should be complete.
 pub trait FileWriter {
-    fn write(&mut self, color: Color);
+    /// Prepare for an image of the passed dimensions (eg. write the header).
+    fn begin(&mut self, width: u16, height: u16) -> Result<()>;
+
+    fn write(&mut self, color: Color) -> Result<()>;
+
+    /// Finish up after the last pixel (eg. write anything buffered, flush the file).
+    fn finish(&mut self) -> Result<()>;
+
+    /// The kind of colors `write()` should be passed.
+    fn pixel_format(&self) -> PixelFormat {
+        PixelFormat::Display
+    }
+}
+
+/// Make sure `got` pixels fit in an image of `width` by `height`, for writers to check with as pixels come in.
+/// With `exact`, it has to be exactly the number of pixels (for checking once they're all written).
+fn check_pixel_count(width: u16, height: u16, got: usize, exact: bool) -> Result<()> {
+    let expected = width as usize * height as usize;
+
+    match got > expected || (exact && got != expected) {
+        true => Err(Error::DimensionMismatch { expected, got }),
+        false => Ok(()),
+    }
+}
+
+/// A path in the temp directory for a test to write a file to, unique to the test (and the process running it).
+#[cfg(test)]
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("raytracing-{}-{}", std::process::id(), name))
+        .to_string_lossy()
+        .into_owned()
 }
 
+mod exr;
+mod hdr;
+mod pfm;
+mod png;
 mod ppm;
+mod tonemap;
 
+pub use exr::{ExrCompression, ExrFile, ExrPrecision};
+pub use hdr::HdrFile;
+pub use pfm::PfmFile;
+pub use png::{PngBitDepth, PngFile};
 pub use ppm::PPMFile;
+pub use tonemap::{OutputTransform, ToneMap};
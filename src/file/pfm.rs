@@ -0,0 +1,170 @@
+use super::{check_pixel_count, FileWriter, PixelFormat};
+use crate::{error::Result, structs::Color};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+/// A portable float map (`.pfm`), storing the unclamped linear radiance as 32-bit floats.
+///
+/// PFM stores rows from the bottom of the image to the top, so the pixels are collected in memory
+/// and written in `finish()`.
+pub struct PfmFile {
+    file: BufWriter<File>,
+    width: u16,
+    height: u16,
+    pixels: Vec<Color>,
+}
+
+impl PfmFile {
+    pub fn new(file: &str) -> Result<Self> {
+        let file = File::create(file)?;
+
+        Ok(PfmFile {
+            file: BufWriter::new(file),
+            width: 0,
+            height: 0,
+            pixels: vec![],
+        })
+    }
+}
+
+impl FileWriter for PfmFile {
+    fn begin(&mut self, width: u16, height: u16) -> Result<()> {
+        self.width = width;
+        self.height = height;
+        self.pixels = Vec::with_capacity(width as usize * height as usize);
+
+        writeln!(self.file, "PF")?;
+        writeln!(self.file, "{} {}", width, height)?;
+        // A negative scale means the floats are little-endian.
+        writeln!(self.file, "-1.0")?;
+
+        Ok(())
+    }
+
+    fn write(&mut self, color: Color) -> Result<()> {
+        self.pixels.push(color);
+        check_pixel_count(self.width, self.height, self.pixels.len(), false)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        check_pixel_count(self.width, self.height, self.pixels.len(), true)?;
+
+        // An image 0 pixels wide has no pixels (or rows) to write, but chunks can't be empty.
+        for row in self.pixels.chunks(self.width.max(1) as usize).rev() {
+            for color in row {
+                for channel in [color.r(), color.g(), color.b()] {
+                    self.file.write_all(&(channel as f32).to_le_bytes())?;
+                }
+            }
+        }
+
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        PixelFormat::Linear
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::temp_path, *};
+
+    /// Write an image, and read it back (going from the bottom row up).
+    fn round_trip(width: u16, height: u16, pixels: &[Color]) -> (String, Vec<[f32; 3]>) {
+        let path = temp_path(&format!("round-trip-{}x{}.pfm", width, height));
+        let mut file = PfmFile::new(&path).unwrap();
+
+        file.begin(width, height).unwrap();
+        for &color in pixels {
+            file.write(color).unwrap();
+        }
+        file.finish().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        // The header is 3 lines of text.
+        let header_length = bytes
+            .iter()
+            .enumerate()
+            .filter(|(_, &byte)| byte == b'\n')
+            .nth(2)
+            .unwrap()
+            .0
+            + 1;
+        let floats: Vec<f32> = bytes[header_length..]
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+
+        (
+            String::from_utf8(bytes[..header_length].to_vec()).unwrap(),
+            floats
+                .chunks_exact(3)
+                .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn round_trips_unclamped_radiance() {
+        let pixels = [
+            Color::new(0, 0, 0),
+            Color::new(0.25, 0.5, 1),
+            Color::new(1000, 0.001, 3.5),
+            Color::new(-1, 2, 1e-6),
+            Color::new(7, 8, 9),
+            Color::new(10, 11, 12),
+        ];
+        let (header, floats) = round_trip(3, 2, &pixels);
+
+        assert_eq!(header, "PF\n3 2\n-1.0\n");
+
+        // The bottom row comes first.
+        let rows: Vec<&[Color]> = pixels.chunks(3).rev().collect();
+        for (color, float) in rows.concat().iter().zip(&floats) {
+            assert_eq!(
+                *float,
+                [color.r() as f32, color.g() as f32, color.b() as f32]
+            );
+        }
+        assert_eq!(floats.len(), 6);
+    }
+
+    #[test]
+    fn rejects_the_wrong_pixel_count() {
+        let path = temp_path("wrong-count.pfm");
+        let mut file = PfmFile::new(&path).unwrap();
+
+        file.begin(2, 1).unwrap();
+        file.write(Color::BLACK).unwrap();
+        assert!(file.finish().is_err());
+        file.write(Color::BLACK).unwrap();
+        assert!(file.write(Color::BLACK).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn writes_empty_images() {
+        for (width, height) in [(0, 3), (3, 0), (0, 0)] {
+            let path = temp_path("empty.pfm");
+            let mut file = PfmFile::new(&path).unwrap();
+
+            file.begin(width, height).unwrap();
+            file.finish().unwrap();
+
+            let bytes = std::fs::read(&path).unwrap();
+            assert_eq!(
+                bytes,
+                format!("PF\n{} {}\n-1.0\n", width, height).into_bytes()
+            );
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
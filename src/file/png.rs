@@ -0,0 +1,171 @@
+use super::{check_pixel_count, FileWriter};
+use crate::{
+    error::{Error, Result},
+    structs::Color,
+};
+use png::{BitDepth, ColorType, Encoder, EncodingError};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    time::Instant,
+};
+
+/// Bits per channel of a PNG file.
+#[derive(Debug, Clone, Copy)]
+pub enum PngBitDepth {
+    Eight,
+    Sixteen,
+}
+
+/// A PNG image.
+///
+/// PNGs are compressed as a whole, so the pixels are collected in memory,
+/// and the file is written in `finish()`.
+pub struct PngFile {
+    file: BufWriter<File>,
+    width: u16,
+    height: u16,
+    bit_depth: PngBitDepth,
+    /// Whether to add an alpha channel (always opaque, as every pixel is rendered).
+    alpha: bool,
+    /// Key-value pairs embedded as text chunks in the file.
+    text: Vec<(String, String)>,
+    pixels: Vec<u8>,
+    /// When the render began, to embed the render time.
+    began_at: Instant,
+}
+
+impl From<EncodingError> for Error {
+    fn from(error: EncodingError) -> Self {
+        match error {
+            EncodingError::IoError(error) => Error::Io(error),
+            error => Error::UnsupportedFormat(error.to_string()),
+        }
+    }
+}
+
+impl PngFile {
+    /// An 8-bit RGB PNG, see the other methods for changing that.
+    pub fn new(file: &str) -> Result<Self> {
+        let file = File::create(file)?;
+
+        Ok(PngFile {
+            file: BufWriter::new(file),
+            width: 0,
+            height: 0,
+            bit_depth: PngBitDepth::Eight,
+            alpha: false,
+            text: vec![],
+            pixels: vec![],
+            began_at: Instant::now(),
+        })
+    }
+
+    pub fn bit_depth(mut self, bit_depth: PngBitDepth) -> Self {
+        self.bit_depth = bit_depth;
+        self
+    }
+
+    pub fn alpha(mut self, alpha: bool) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Embed some metadata (eg. the sample count) as text in the file.
+    pub fn text<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.text.push((key.into(), value.into()));
+        self
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        let channels = match self.alpha {
+            true => 4,
+            false => 3,
+        };
+
+        match self.bit_depth {
+            PngBitDepth::Eight => channels,
+            PngBitDepth::Sixteen => channels * 2,
+        }
+    }
+}
+
+impl FileWriter for PngFile {
+    fn begin(&mut self, width: u16, height: u16) -> Result<()> {
+        self.width = width;
+        self.height = height;
+        self.pixels = Vec::with_capacity(width as usize * height as usize * self.bytes_per_pixel());
+        self.began_at = Instant::now();
+
+        Ok(())
+    }
+
+    fn write(&mut self, color: Color) -> Result<()> {
+        match self.bit_depth {
+            PngBitDepth::Eight => {
+                self.pixels.extend(color.to_rgb8());
+
+                if self.alpha {
+                    self.pixels.push(u8::MAX);
+                }
+            }
+            // PNGs store 16-bit values big-endian.
+            PngBitDepth::Sixteen => {
+                for channel in color.to_rgb16() {
+                    self.pixels.extend(channel.to_be_bytes());
+                }
+
+                if self.alpha {
+                    self.pixels.extend(u16::MAX.to_be_bytes());
+                }
+            }
+        }
+
+        check_pixel_count(
+            self.width,
+            self.height,
+            self.pixels.len() / self.bytes_per_pixel(),
+            false,
+        )
+    }
+
+    /// Encode the collected pixels and write them (with the header and metadata) to the file.
+    fn finish(&mut self) -> Result<()> {
+        check_pixel_count(
+            self.width,
+            self.height,
+            self.pixels.len() / self.bytes_per_pixel(),
+            true,
+        )?;
+
+        let mut encoder = Encoder::new(&mut self.file, self.width as u32, self.height as u32);
+
+        encoder.set_color(match self.alpha {
+            true => ColorType::Rgba,
+            false => ColorType::Rgb,
+        });
+        encoder.set_depth(match self.bit_depth {
+            PngBitDepth::Eight => BitDepth::Eight,
+            PngBitDepth::Sixteen => BitDepth::Sixteen,
+        });
+
+        let render_time = format!("{:.2}s", self.began_at.elapsed().as_secs_f64());
+
+        for (key, value) in self
+            .text
+            .iter()
+            .cloned()
+            .chain([("Render Time".to_string(), render_time)])
+        {
+            encoder.add_text_chunk(key, value)?;
+        }
+
+        let mut writer = encoder.write_header()?;
+
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        self.file.flush()?;
+
+        Ok(())
+    }
+}
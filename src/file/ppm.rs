@@ -1,28 +1,58 @@
-use super::FileWriter;
-use crate::structs::Color;
-use std::{fs::File, io::Write};
+use super::{check_pixel_count, FileWriter};
+use crate::{error::Result, structs::Color};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
 
 pub struct PPMFile {
-    file: Box<File>,
+    file: BufWriter<File>,
+    width: u16,
+    height: u16,
+    written: usize,
 }
 
 impl PPMFile {
-    pub fn new(file: &str, width: u16, height: u16) -> Self {
-        let mut file = File::create(file).expect("File creation failed.");
+    pub fn new(file: &str) -> Result<Self> {
+        let file = File::create(file)?;
 
-        writeln!(file, "P3").unwrap();
-        writeln!(file, "{} {}", width, height).unwrap();
-        writeln!(file, "255").unwrap();
-
-        PPMFile {
-            file: Box::new(file),
-        }
+        Ok(PPMFile {
+            file: BufWriter::new(file),
+            width: 0,
+            height: 0,
+            written: 0,
+        })
     }
 }
 
 impl FileWriter for PPMFile {
-    fn write(&mut self, color: Color) {
-        writeln!(self.file, "{} {} {}", color.r(), color.g(), color.b())
-            .expect("Writing to file failed.");
+    fn begin(&mut self, width: u16, height: u16) -> Result<()> {
+        self.width = width;
+        self.height = height;
+
+        writeln!(self.file, "P3")?;
+        writeln!(self.file, "{} {}", width, height)?;
+        writeln!(self.file, "255")?;
+
+        Ok(())
+    }
+
+    fn write(&mut self, color: Color) -> Result<()> {
+        self.written += 1;
+        check_pixel_count(self.width, self.height, self.written, false)?;
+
+        let [r, g, b] = color.to_rgb8();
+
+        writeln!(self.file, "{} {} {}", r, g, b)?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        check_pixel_count(self.width, self.height, self.written, true)?;
+
+        self.file.flush()?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,115 @@
+//! The output transform, turning the linear radiance from the renderer into colors ready for display.
+
+use crate::structs::Color;
+
+/// Operators for squashing radiance (which can go way past 1.0) into the 0 - 1 a display can show.
+#[derive(Debug, Clone, Copy)]
+pub enum ToneMap {
+    /// Just clip everything brighter than white.
+    Clamp,
+    /// `c / (1 + c)`, which never quite reaches white.
+    Reinhard,
+    /// Reinhard, but with `white` (in linear radiance, above 0) mapping to exactly white.
+    ExtendedReinhard { white: f64 },
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve.
+    /// https://knarkowicz.wordpress.com/2016/01/06/aces-filmic-tone-mapping-curve/
+    Aces,
+    /// An approximation of AgX (as in Blender), which desaturates bright colors instead of skewing their hue.
+    /// https://iolite-engine.com/blog_posts/minimal_agx_implementation
+    Agx,
+}
+
+/// The "inset" matrix AgX applies before its curve, and its inverse applied after.
+/// Rows are the output channels.
+const AGX_INSET: [[f64; 3]; 3] = [
+    [0.842479062253094, 0.0784335999999992, 0.0792237451477643],
+    [0.0423282422610123, 0.878468636469772, 0.0791661274605434],
+    [0.0423756549057051, 0.0784336, 0.879142973793104],
+];
+const AGX_OUTSET: [[f64; 3]; 3] = [
+    [1.19687900512017, -0.0980208811401368, -0.0990297440797205],
+    [-0.0528968517574562, 1.15190312990417, -0.0989611768448433],
+    [-0.0529716355144438, -0.0980434501171241, 1.15107367264116],
+];
+/// The range of exposure values (around middle grey) AgX's log encoding covers.
+const AGX_MIN_EV: f64 = -12.47393;
+const AGX_MAX_EV: f64 = 4.026069;
+
+fn apply_matrix(matrix: &[[f64; 3]; 3], [r, g, b]: [f64; 3]) -> [f64; 3] {
+    matrix.map(|row| row[0] * r + row[1] * g + row[2] * b)
+}
+
+impl ToneMap {
+    /// Map a linear color to a linear color in the 0 - 1 range.
+    pub fn apply(&self, color: Color) -> Color {
+        let channels = [color.r(), color.g(), color.b()].map(|c| c.max(0.0));
+
+        let [r, g, b] = match *self {
+            ToneMap::Clamp => channels.map(|c| c.min(1.0)),
+            ToneMap::Reinhard => channels.map(|c| c / (1.0 + c)),
+            ToneMap::ExtendedReinhard { white } => {
+                // A white of 0 (or less) would divide by 0, so it's kept just above (where anything that isn't black turns white).
+                let white = white.max(1e-6);
+
+                channels.map(|c| (c * (1.0 + c / (white * white)) / (1.0 + c)).min(1.0))
+            }
+            ToneMap::Aces => channels.map(|c| {
+                ((c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14)).clamp(0.0, 1.0)
+            }),
+            ToneMap::Agx => {
+                let encoded = apply_matrix(&AGX_INSET, channels).map(|c| {
+                    // Log encoding, so the curve works in stops rather than linear values.
+                    let ev = c.max(1e-10).log2().clamp(AGX_MIN_EV, AGX_MAX_EV);
+                    let x = (ev - AGX_MIN_EV) / (AGX_MAX_EV - AGX_MIN_EV);
+
+                    // Polynomial fit of AgX's base contrast curve.
+                    let x2 = x * x;
+                    let x4 = x2 * x2;
+                    15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x
+                        + 0.4298 * x2
+                        + 0.1191 * x
+                        - 0.00232
+                });
+
+                // The curve outputs display-encoded values, so take them back to linear.
+                apply_matrix(&AGX_OUTSET, encoded).map(|c| c.clamp(0.0, 1.0).powf(2.2))
+            }
+        };
+
+        Color::new(r, g, b)
+    }
+}
+
+/// Encode a linear channel value with the sRGB transfer function.
+fn linear_to_srgb(c: f64) -> f64 {
+    match c <= 0.0031308 {
+        true => 12.92 * c,
+        false => 1.055 * c.powf(1.0 / 2.4) - 0.055,
+    }
+}
+
+/// The full transform from the renderer's output to what is written in an image file:
+/// exposure, then tone mapping, then sRGB encoding.
+#[derive(Debug, Clone, Copy)]
+pub struct OutputTransform {
+    /// Exposure adjustment in stops, each stop doubling (or halving) the brightness.
+    exposure: f64,
+    tone_map: ToneMap,
+}
+
+impl OutputTransform {
+    pub fn apply(&self, color: Color) -> Color {
+        let exposed = color * 2.0_f64.powf(self.exposure);
+        let mapped = self.tone_map.apply(exposed);
+
+        Color::new(
+            linear_to_srgb(mapped.r()),
+            linear_to_srgb(mapped.g()),
+            linear_to_srgb(mapped.b()),
+        )
+    }
+
+    pub fn new(exposure: f64, tone_map: ToneMap) -> Self {
+        OutputTransform { exposure, tone_map }
+    }
+}
@@ -0,0 +1,520 @@
+//! Loading of images (PNG, JPEG, PPM and Radiance HDR) into image textures.
+//!
+//! 8-bit (and 16-bit) formats store colors as they should be displayed, so they are converted from sRGB
+//! into the linear colors the renderer works with. HDR images are already linear.
+
+use super::ImportError;
+use crate::{structs::Color, textures::ImageTexture};
+use jpeg_decoder::{Decoder as JpegDecoder, PixelFormat as JpegPixelFormat};
+use png::{ColorType, Decoder as PngDecoder, DecodingError, Transformations};
+use std::{fs, io::BufReader, path::Path};
+
+/// An image as read from a file, before it becomes a texture.
+struct Image {
+    width: usize,
+    height: usize,
+    /// The pixels (as linear colors), row by row from the top.
+    pixels: Vec<Color>,
+}
+
+/// Decode a channel value (0 - 1) from the sRGB transfer function.
+fn srgb_to_linear(c: f64) -> f64 {
+    match c <= 0.04045 {
+        true => c / 12.92,
+        false => ((c + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+/// A linear color from sRGB channels out of a maximum of `max`.
+fn from_srgb(r: u32, g: u32, b: u32, max: u32) -> Color {
+    let channel = |c: u32| srgb_to_linear(c as f64 / max as f64);
+
+    Color::new(channel(r), channel(g), channel(b))
+}
+
+fn load_png(path: &Path, bytes: &[u8]) -> Result<Image, ImportError> {
+    let to_error = |error: DecodingError| match error {
+        DecodingError::IoError(error) => ImportError::io(path, error),
+        error => ImportError::decode(path, error.to_string()),
+    };
+
+    let mut decoder = PngDecoder::new(bytes);
+    // Turn palettes and less than 8-bit grayscale into plain 8-bit channels.
+    decoder.set_transformations(Transformations::EXPAND);
+
+    let mut reader = decoder.read_info().map_err(to_error)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(to_error)?;
+    let buffer = &buffer[..info.buffer_size()];
+
+    let channels = match info.color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::Rgb => 3,
+        ColorType::Rgba => 4,
+        ColorType::Indexed => {
+            return Err(ImportError::decode(path, "Palette wasn't expanded."));
+        }
+    };
+
+    // Either 1 or 2 (big-endian) bytes per channel, after the expansion.
+    let bytes_per_channel = buffer.len() / (info.width as usize * info.height as usize * channels);
+    let (values, max): (Vec<u32>, u32) = match bytes_per_channel {
+        1 => (
+            buffer.iter().map(|&byte| byte as u32).collect(),
+            u8::MAX as u32,
+        ),
+        _ => (
+            buffer
+                .chunks_exact(2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as u32)
+                .collect(),
+            u16::MAX as u32,
+        ),
+    };
+
+    // Alpha isn't used, the texture's surface is always opaque.
+    let pixels = values
+        .chunks_exact(channels)
+        .map(|pixel| match channels {
+            1 | 2 => from_srgb(pixel[0], pixel[0], pixel[0], max),
+            _ => from_srgb(pixel[0], pixel[1], pixel[2], max),
+        })
+        .collect();
+
+    Ok(Image {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    })
+}
+
+fn load_jpeg(path: &Path, bytes: &[u8]) -> Result<Image, ImportError> {
+    let mut decoder = JpegDecoder::new(BufReader::new(bytes));
+    let data = decoder
+        .decode()
+        .map_err(|error| ImportError::decode(path, error.to_string()))?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| ImportError::decode(path, "Missing image info."))?;
+
+    let pixels = match info.pixel_format {
+        JpegPixelFormat::L8 => data
+            .iter()
+            .map(|&l| from_srgb(l as u32, l as u32, l as u32, 255))
+            .collect(),
+        // The decoder gives 16-bit values in native byte order.
+        JpegPixelFormat::L16 => data
+            .chunks_exact(2)
+            .map(|bytes| {
+                let l = u16::from_ne_bytes([bytes[0], bytes[1]]) as u32;
+                from_srgb(l, l, l, u16::MAX as u32)
+            })
+            .collect(),
+        JpegPixelFormat::RGB24 => data
+            .chunks_exact(3)
+            .map(|rgb| from_srgb(rgb[0] as u32, rgb[1] as u32, rgb[2] as u32, 255))
+            .collect(),
+        // The ink covers (1 - cyan) of the red, and so on, with black darkening everything.
+        JpegPixelFormat::CMYK32 => data
+            .chunks_exact(4)
+            .map(|cmyk| {
+                let ink = |c: u8| (255 - c as u32) * (255 - cmyk[3] as u32) / 255;
+                from_srgb(ink(cmyk[0]), ink(cmyk[1]), ink(cmyk[2]), 255)
+            })
+            .collect(),
+    };
+
+    Ok(Image {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    })
+}
+
+/// Reads the whitespace separated tokens of a PPM's header, skipping comments (from a `#` to the end of the line).
+struct PpmHeader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl PpmHeader<'_> {
+    fn next_token(&mut self) -> Option<&[u8]> {
+        loop {
+            match self.bytes.get(self.position)? {
+                b'#' => {
+                    while self
+                        .bytes
+                        .get(self.position)
+                        .is_some_and(|&byte| byte != b'\n')
+                    {
+                        self.position += 1;
+                    }
+                }
+                byte if byte.is_ascii_whitespace() => self.position += 1,
+                _ => break,
+            }
+        }
+
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| !byte.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+
+        Some(&self.bytes[start..self.position])
+    }
+
+    fn next_number(&mut self) -> Option<u32> {
+        std::str::from_utf8(self.next_token()?).ok()?.parse().ok()
+    }
+}
+
+/// Load a PPM (or PGM) image, either as text (`P3`, like `PPMFile` writes, and `P2`) or binary (`P6` and `P5`).
+fn load_ppm(path: &Path, bytes: &[u8]) -> Result<Image, ImportError> {
+    let invalid = || ImportError::decode(path, "Invalid PPM header.");
+
+    let mut header = PpmHeader { bytes, position: 0 };
+
+    let (channels, is_binary) = match header.next_token() {
+        Some(b"P2") => (1, false),
+        Some(b"P3") => (3, false),
+        Some(b"P5") => (1, true),
+        Some(b"P6") => (3, true),
+        _ => return Err(ImportError::decode(path, "Not a PPM (or PGM) image.")),
+    };
+
+    let width = header.next_number().ok_or_else(invalid)? as usize;
+    let height = header.next_number().ok_or_else(invalid)? as usize;
+    let max = header.next_number().ok_or_else(invalid)?;
+
+    if max == 0 || max > u16::MAX as u32 {
+        return Err(invalid());
+    }
+
+    if width == 0 || height == 0 {
+        return Err(ImportError::decode(path, "The image is empty."));
+    }
+
+    // Every value takes at least a byte (2 in binary files going past 255), so huge sizes in the header
+    // are caught here, before anything is allocated for them.
+    let bytes_per_value = match is_binary && max > u8::MAX as u32 {
+        true => 2,
+        false => 1,
+    };
+    let count = width
+        .checked_mul(height)
+        .and_then(|pixels| pixels.checked_mul(channels))
+        .filter(|count| {
+            count.saturating_mul(bytes_per_value) <= bytes.len().saturating_sub(header.position)
+        })
+        .ok_or_else(|| ImportError::decode(path, "The image data is truncated."))?;
+    let values: Vec<u32> = match is_binary {
+        false => (0..count)
+            .map(|_| header.next_number().ok_or_else(invalid))
+            .collect::<Result<_, _>>()?,
+        true => {
+            // Exactly one whitespace byte separates the header from the binary data.
+            let data = bytes.get(header.position + 1..).unwrap_or_default();
+
+            match max > u8::MAX as u32 {
+                true => data
+                    .chunks_exact(2)
+                    .take(count)
+                    .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as u32)
+                    .collect(),
+                false => data.iter().take(count).map(|&byte| byte as u32).collect(),
+            }
+        }
+    };
+
+    if values.len() < count {
+        return Err(ImportError::decode(path, "The image data is truncated."));
+    }
+
+    let pixels = values
+        .chunks_exact(channels)
+        .map(|pixel| match channels {
+            1 => from_srgb(pixel[0], pixel[0], pixel[0], max),
+            _ => from_srgb(pixel[0], pixel[1], pixel[2], max),
+        })
+        .collect();
+
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Decode a pixel in Radiance's shared-exponent format.
+fn from_rgbe([r, g, b, e]: [u8; 4]) -> Color {
+    if e == 0 {
+        return Color::BLACK;
+    }
+
+    // The mantissas are out of 256, and are rounded to the middle of their range.
+    let scale = 2.0_f64.powi(e as i32 - 128 - 8);
+
+    Color::new(
+        (r as f64 + 0.5) * scale,
+        (g as f64 + 0.5) * scale,
+        (b as f64 + 0.5) * scale,
+    )
+}
+
+/// Load a Radiance HDR image, either flat (as `HdrFile` writes) or run-length encoded.
+fn load_hdr(path: &Path, bytes: &[u8]) -> Result<Image, ImportError> {
+    let truncated = || ImportError::decode(path, "The image data is truncated.");
+
+    // The header is lines of text, up to an empty one, followed by the resolution line.
+    let mut lines = bytes.split(|&byte| byte == b'\n');
+    let mut position = 0;
+    let mut next_line = || -> Option<String> {
+        let line = lines.next()?;
+        position += line.len() + 1;
+        Some(String::from_utf8_lossy(line).trim().to_string())
+    };
+
+    if !next_line().is_some_and(|line| line.starts_with("#?")) {
+        return Err(ImportError::decode(path, "Not a Radiance HDR image."));
+    }
+
+    loop {
+        let line = next_line().ok_or_else(truncated)?;
+
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(ImportError::decode(
+                    path,
+                    format!("Unsupported format `{}`.", format),
+                ));
+            }
+        }
+    }
+
+    let resolution = next_line().ok_or_else(truncated)?;
+    let (height, width) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+        ["-Y", height, "+X", width] => (height.parse::<usize>().ok(), width.parse::<usize>().ok()),
+        _ => (None, None),
+    };
+    let (Some(height), Some(width)) = (height, width) else {
+        return Err(ImportError::decode(
+            path,
+            format!("Unsupported resolution `{}`.", resolution),
+        ));
+    };
+
+    if width == 0 || height == 0 {
+        return Err(ImportError::decode(path, "The image is empty."));
+    }
+
+    let data = bytes.get(position..).unwrap_or_default();
+
+    // The fewest bytes a scanline can take, which is 2 per channel for every 127 pixels when they're all runs
+    // of the same value, so huge sizes in the header are caught here, before anything is allocated for them.
+    let scanline_bytes = match (8..0x8000).contains(&width) {
+        true => 4 + 8 * width.div_ceil(127),
+        false => width.saturating_mul(4),
+    };
+    let count = width
+        .checked_mul(height)
+        .filter(|_| height.saturating_mul(scanline_bytes) <= data.len())
+        .ok_or_else(truncated)?;
+
+    let mut data = data.iter().copied();
+    let mut next_byte = || data.next().ok_or_else(truncated);
+
+    let mut pixels = Vec::with_capacity(count);
+    let mut scanline = vec![[0_u8; 4]; width];
+
+    for _ in 0..height {
+        let start = [next_byte()?, next_byte()?, next_byte()?, next_byte()?];
+
+        // Run-length encoded scanlines start with 2, 2 and the width, which can't be a valid pixel.
+        let is_rle = (8..0x8000).contains(&width)
+            && start[0] == 2
+            && start[1] == 2
+            && ((start[2] as usize) << 8 | start[3] as usize) == width;
+
+        match is_rle {
+            true => {
+                // Each channel is encoded separately, as runs of the same value or of different ones.
+                for channel in 0..4 {
+                    let mut x = 0;
+
+                    while x < width {
+                        let count = next_byte()? as usize;
+
+                        let (length, repeated) = match count > 128 {
+                            true => (count - 128, Some(next_byte()?)),
+                            false => (count, None),
+                        };
+
+                        if length == 0 || x + length > width {
+                            return Err(ImportError::decode(path, "Invalid run length."));
+                        }
+
+                        for pixel in &mut scanline[x..x + length] {
+                            pixel[channel] = match repeated {
+                                Some(value) => value,
+                                None => next_byte()?,
+                            };
+                        }
+
+                        x += length;
+                    }
+                }
+            }
+            false => {
+                scanline[0] = start;
+
+                for pixel in &mut scanline[1..] {
+                    *pixel = [next_byte()?, next_byte()?, next_byte()?, next_byte()?];
+                }
+            }
+        }
+
+        pixels.extend(scanline.iter().map(|&rgbe| from_rgbe(rgbe)));
+    }
+
+    Ok(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Load an image file into a texture, picking the format from the file's extension.
+pub fn load_image<P: AsRef<Path>>(path: P) -> Result<ImageTexture, ImportError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|error| ImportError::io(path, error))?;
+
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+
+    let image = match extension.as_deref() {
+        Some("png") => load_png(path, &bytes)?,
+        Some("jpg" | "jpeg") => load_jpeg(path, &bytes)?,
+        Some("ppm" | "pgm") => load_ppm(path, &bytes)?,
+        Some("hdr" | "pic") => load_hdr(path, &bytes)?,
+        _ => return Err(ImportError::decode(path, "Unsupported image format.")),
+    };
+
+    if image.width == 0 || image.height == 0 {
+        return Err(ImportError::decode(path, "The image is empty."));
+    }
+
+    Ok(ImageTexture::new(image.width, image.height, image.pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hdr(resolution: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes =
+            format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n{}\n", resolution).into_bytes();
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn loads_flat_hdr() {
+        // A pixel of 1 in every channel, and a black one.
+        let bytes = hdr("-Y 1 +X 2", &[128, 128, 128, 129, 0, 0, 0, 0]);
+        let image = load_hdr(Path::new("test.hdr"), &bytes).unwrap();
+
+        assert_eq!((image.width, image.height), (2, 1));
+        assert!((image.pixels[0].r() - 1.00390625).abs() < 1e-9);
+        assert!(image.pixels[1].is_black());
+    }
+
+    #[test]
+    fn loads_run_length_encoded_hdr() {
+        // 8 pixels, each channel a single run of the same value.
+        let mut data = vec![2, 2, 0, 8];
+        for value in [128, 64, 32, 129] {
+            data.extend_from_slice(&[128 + 8, value]);
+        }
+        let image = load_hdr(Path::new("test.hdr"), &hdr("-Y 1 +X 8", &data)).unwrap();
+
+        assert_eq!(image.pixels.len(), 8);
+        assert!(image
+            .pixels
+            .iter()
+            .all(|pixel| pixel.b() == image.pixels[0].b()));
+        assert!((image.pixels[7].r() - 1.00390625).abs() < 1e-9);
+        assert!((image.pixels[7].g() - 0.50390625).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_malformed_hdr_headers() {
+        for resolution in [
+            "-Y 1 +X 0",
+            "-Y 0 +X 1",
+            "-Y 4000000000 +X 4000000000",
+            "+X 1 -Y 1",
+        ] {
+            let bytes = hdr(resolution, &[128, 128, 128, 128]);
+            assert!(
+                load_hdr(Path::new("test.hdr"), &bytes).is_err(),
+                "{}",
+                resolution
+            );
+        }
+
+        // One pixel short.
+        let bytes = hdr("-Y 2 +X 1", &[128, 128, 128, 128]);
+        assert!(load_hdr(Path::new("test.hdr"), &bytes).is_err());
+    }
+
+    #[test]
+    fn loads_ppm() {
+        let text = load_ppm(
+            Path::new("test.ppm"),
+            b"P3\n# comment\n2 1\n255\n255 0 0  0 0 0\n",
+        )
+        .unwrap();
+        let binary = load_ppm(
+            Path::new("test.ppm"),
+            b"P6 2 1 255\n\xff\x00\x00\x00\x00\x00",
+        )
+        .unwrap();
+
+        for image in [text, binary] {
+            assert_eq!((image.width, image.height), (2, 1));
+            assert_eq!(
+                [
+                    image.pixels[0].r(),
+                    image.pixels[0].g(),
+                    image.pixels[0].b()
+                ],
+                [1.0, 0.0, 0.0]
+            );
+            assert!(image.pixels[1].is_black());
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_ppm_headers() {
+        for bytes in [
+            &b"P3 0 1 255\n"[..],
+            b"P6 4000000000 4000000000 255\n\x00\x00\x00",
+            b"P3 18446744073709551615 2 255\n0 0 0",
+            b"P6 2 1 255\n\x00\x00\x00",
+            b"P3 1 1\n",
+        ] {
+            assert!(load_ppm(Path::new("test.ppm"), bytes).is_err());
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Importers for loading scene data from files.
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// An error while importing a file.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The file (or one it refers to) couldn't be read.
+    Io { path: PathBuf, error: io::Error },
+    /// The file has something in it we can't make sense of.
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// A binary file (eg. an image) that isn't valid, or uses features we don't support.
+    Decode { path: PathBuf, message: String },
+}
+
+impl ImportError {
+    fn io(path: &Path, error: io::Error) -> Self {
+        ImportError::Io {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+
+    fn parse<S: Into<String>>(path: &Path, line: usize, message: S) -> Self {
+        ImportError::Parse {
+            path: path.to_path_buf(),
+            line,
+            message: message.into(),
+        }
+    }
+
+    fn decode<S: Into<String>>(path: &Path, message: S) -> Self {
+        ImportError::Decode {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ImportError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ImportError::Decode { path, message } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ImportError::Io { error, .. } => Some(error),
+            ImportError::Parse { .. } | ImportError::Decode { .. } => None,
+        }
+    }
+}
+
+/// Parse the `index`th argument of a statement as a float.
+fn parse_float(path: &Path, line: usize, args: &[&str], index: usize) -> Result<f64, ImportError> {
+    let arg = args.get(index).ok_or_else(|| {
+        ImportError::parse(
+            path,
+            line,
+            format!("Expected at least {} numbers.", index + 1),
+        )
+    })?;
+
+    arg.parse()
+        .map_err(|_| ImportError::parse(path, line, format!("Invalid number `{}`.", arg)))
+}
+
+mod image;
+mod mtl;
+mod obj;
+
+pub use image::load_image;
+pub use obj::{load_obj, load_obj_into};
@@ -0,0 +1,164 @@
+//! Parsing of Wavefront material (`.mtl`) files.
+//!
+//! There's no way to express everything MTL can describe with the materials we have, so every
+//! entry is mapped onto the closest one of `Lambertian`, `Metal` or `Dielectric`.
+
+use super::{parse_float, ImportError};
+use crate::{
+    color,
+    materials::{Dielectric, Lambertian, Material, Metal},
+    structs::Color,
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+/// The properties of a single `newmtl` entry we care about.
+#[derive(Debug)]
+struct MtlEntry {
+    /// Diffuse color.
+    kd: [f64; 3],
+    /// Specular color.
+    ks: [f64; 3],
+    /// Specular exponent (0 - 1000), higher is shinier.
+    ns: f64,
+    /// Index of refraction.
+    ni: f64,
+    /// Opacity ("dissolve").
+    d: f64,
+    /// Illumination model.
+    illum: u8,
+}
+
+impl Default for MtlEntry {
+    fn default() -> Self {
+        MtlEntry {
+            kd: [0.8, 0.8, 0.8],
+            ks: [0.0, 0.0, 0.0],
+            ns: 0.0,
+            ni: 1.5,
+            d: 1.0,
+            illum: 1,
+        }
+    }
+}
+
+impl MtlEntry {
+    /// Pick the closest material we have to what the entry describes.
+    fn to_material(&self) -> Arc<dyn Material + Sync + Send> {
+        // Illumination models 4, 6, 7 and 9 are the ones with transparency / refraction.
+        let is_transparent = matches!(self.illum, 4 | 6 | 7 | 9) || self.d < 1.0;
+        // Illumination models 3 and 5 are the ones with ray-traced reflections.
+        let is_reflective = matches!(self.illum, 3 | 5)
+            || (self.illum == 2
+                && self.kd.iter().all(|&c| c == 0.0)
+                && self.ks.iter().any(|&c| c > 0.0));
+
+        if is_transparent {
+            Arc::new(Dielectric::new(self.ni))
+        } else if is_reflective {
+            // A rough conversion from the (phong) specular exponent to roughness.
+            let fuzz = (2.0 / (self.ns.max(0.0) + 2.0)).sqrt();
+            Arc::new(Metal::new(to_color(self.ks), fuzz))
+        } else {
+            Arc::new(Lambertian::new(to_color(self.kd)))
+        }
+    }
+}
+
+/// Convert an MTL color to a `Color`.
+fn to_color([r, g, b]: [f64; 3]) -> Color {
+    color!(r, g, b)
+}
+
+/// Parse a color statement, which is either three numbers or a single one for all channels.
+fn parse_color(path: &Path, line: usize, args: &[&str]) -> Result<[f64; 3], ImportError> {
+    if matches!(args.first(), Some(&"spectral") | Some(&"xyz")) {
+        return Err(ImportError::parse(
+            path,
+            line,
+            "Only RGB colors are supported.",
+        ));
+    }
+
+    let r = parse_float(path, line, args, 0)?;
+
+    match args.len() {
+        1 => Ok([r, r, r]),
+        _ => Ok([
+            r,
+            parse_float(path, line, args, 1)?,
+            parse_float(path, line, args, 2)?,
+        ]),
+    }
+}
+
+/// Load all the materials from an MTL file, keyed by their names.
+pub(super) fn load_mtl(
+    path: &Path,
+) -> Result<HashMap<String, Arc<dyn Material + Sync + Send>>, ImportError> {
+    let file = File::open(path).map_err(|error| ImportError::io(path, error))?;
+
+    let mut entries: Vec<(String, MtlEntry)> = vec![];
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.map_err(|error| ImportError::io(path, error))?;
+
+        // Everything after a `#` is a comment.
+        let line = line.split('#').next().unwrap_or_default();
+        let mut parts = line.split_whitespace();
+
+        let Some(keyword) = parts.next() else {
+            continue;
+        };
+        let args: Vec<&str> = parts.collect();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(ImportError::parse(path, line_no, "Material has no name."));
+            }
+
+            entries.push((args.join(" "), MtlEntry::default()));
+            continue;
+        }
+
+        // Anything else needs a material to describe.
+        let Some((_, entry)) = entries.last_mut() else {
+            return Err(ImportError::parse(
+                path,
+                line_no,
+                format!("`{}` before any `newmtl`.", keyword),
+            ));
+        };
+
+        match keyword {
+            "Kd" => entry.kd = parse_color(path, line_no, &args)?,
+            "Ks" => entry.ks = parse_color(path, line_no, &args)?,
+            "Ns" => entry.ns = parse_float(path, line_no, &args, 0)?,
+            "Ni" => entry.ni = parse_float(path, line_no, &args, 0)?,
+            "d" => entry.d = parse_float(path, line_no, &args, 0)?,
+            // Transparency, the inverse of `d`.
+            "Tr" => entry.d = 1.0 - parse_float(path, line_no, &args, 0)?,
+            "illum" => {
+                entry.illum = args
+                    .first()
+                    .and_then(|arg| arg.parse().ok())
+                    .ok_or_else(|| {
+                        ImportError::parse(path, line_no, "Invalid illumination model.")
+                    })?
+            }
+            // Everything else (texture maps, ambient color, etc.) has nothing to map to.
+            _ => {}
+        }
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|(name, entry)| (name, entry.to_material()))
+        .collect())
+}
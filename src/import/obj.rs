@@ -0,0 +1,407 @@
+//! Parsing of Wavefront `.obj` files into triangle meshes.
+//!
+//! Every group (`g` / `o`) and material (`usemtl`) combination becomes its own `TriangleMesh`,
+//! all sharing the same vertex buffers.
+
+use super::{mtl::load_mtl, parse_float, ImportError};
+use crate::{
+    color,
+    materials::{Lambertian, Material},
+    objects::TriangleMesh,
+    point3,
+    structs::{Point3, Scene, Vec3},
+    vec3,
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+/// Indices of a face's corner into the position, UV and normal lists of the file.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// The triangles of one group + material combination.
+#[derive(Debug)]
+struct MeshData {
+    material: Option<String>,
+    indices: Vec<[usize; 3]>,
+    /// Do all the corners of all the triangles have normals / UVs ?
+    has_normals: bool,
+    has_uvs: bool,
+}
+
+/// Everything collected while parsing the file.
+#[derive(Debug, Default)]
+struct ObjData {
+    positions: Vec<Point3>,
+    uvs: Vec<(f64, f64)>,
+    normals: Vec<Vec3>,
+
+    /// The unified vertex buffers the meshes index into.
+    /// OBJ indexes positions, UVs and normals separately, so every distinct combination of them
+    /// becomes one vertex. Missing UVs / normals are zeroes and are never used.
+    vertex_positions: Vec<Point3>,
+    vertex_uvs: Vec<(f64, f64)>,
+    vertex_normals: Vec<Vec3>,
+    vertex_lookup: HashMap<Corner, usize>,
+
+    meshes: Vec<MeshData>,
+    /// Lookup for the mesh of a group + material combination.
+    mesh_lookup: HashMap<(String, Option<String>), usize>,
+
+    materials: HashMap<String, Arc<dyn Material + Sync + Send>>,
+}
+
+impl ObjData {
+    /// Resolve an OBJ index (1-based, or negative for counting back from the end) to a 0-based one.
+    fn resolve_index(
+        path: &Path,
+        line: usize,
+        index: &str,
+        count: usize,
+    ) -> Result<usize, ImportError> {
+        let parsed: i64 = index
+            .parse()
+            .map_err(|_| ImportError::parse(path, line, format!("Invalid index `{}`.", index)))?;
+
+        let resolved = match parsed {
+            i if i > 0 => i - 1,
+            i if i < 0 => count as i64 + i,
+            _ => -1,
+        };
+
+        match resolved >= 0 && resolved < count as i64 {
+            true => Ok(resolved as usize),
+            false => Err(ImportError::parse(
+                path,
+                line,
+                format!("Index `{}` is out of bounds.", index),
+            )),
+        }
+    }
+
+    /// Parse one corner of a face (`v`, `v/vt`, `v//vn` or `v/vt/vn`).
+    fn parse_corner(&self, path: &Path, line: usize, corner: &str) -> Result<Corner, ImportError> {
+        let mut parts = corner.split('/');
+
+        let position = Self::resolve_index(
+            path,
+            line,
+            parts.next().unwrap_or_default(),
+            self.positions.len(),
+        )?;
+
+        let uv = match parts.next() {
+            Some("") | None => None,
+            Some(index) => Some(Self::resolve_index(path, line, index, self.uvs.len())?),
+        };
+
+        let normal = match parts.next() {
+            Some("") | None => None,
+            Some(index) => Some(Self::resolve_index(path, line, index, self.normals.len())?),
+        };
+
+        if parts.next().is_some() {
+            return Err(ImportError::parse(
+                path,
+                line,
+                format!("Invalid face corner `{}`.", corner),
+            ));
+        }
+
+        Ok((position, uv, normal))
+    }
+
+    /// Get the index of the unified vertex for a corner, creating it if needed.
+    fn vertex(&mut self, corner: Corner) -> usize {
+        if let Some(&index) = self.vertex_lookup.get(&corner) {
+            return index;
+        }
+
+        let (position, uv, normal) = corner;
+
+        self.vertex_positions.push(self.positions[position]);
+        self.vertex_uvs
+            .push(uv.map_or((0.0, 0.0), |uv| self.uvs[uv]));
+        self.vertex_normals
+            .push(normal.map_or(vec3!(0, 0, 0), |normal| self.normals[normal]));
+
+        let index = self.vertex_positions.len() - 1;
+        self.vertex_lookup.insert(corner, index);
+        index
+    }
+
+    /// Add a face (with any number of corners) to the mesh of the current group and material.
+    fn add_face(
+        &mut self,
+        path: &Path,
+        line: usize,
+        args: &[&str],
+        group: &str,
+        material: &Option<String>,
+    ) -> Result<(), ImportError> {
+        if args.len() < 3 {
+            return Err(ImportError::parse(
+                path,
+                line,
+                "A face needs at least 3 vertices.",
+            ));
+        }
+
+        let corners = args
+            .iter()
+            .map(|corner| self.parse_corner(path, line, corner))
+            .collect::<Result<Vec<Corner>, ImportError>>()?;
+
+        let has_uvs = corners.iter().all(|(_, uv, _)| uv.is_some());
+        let has_normals = corners.iter().all(|(_, _, normal)| normal.is_some());
+        let vertices: Vec<usize> = corners.into_iter().map(|c| self.vertex(c)).collect();
+
+        let key = (group.to_string(), material.clone());
+        let mesh_index = match self.mesh_lookup.get(&key) {
+            Some(&index) => index,
+            None => {
+                self.meshes.push(MeshData {
+                    material: material.clone(),
+                    indices: vec![],
+                    has_normals: true,
+                    has_uvs: true,
+                });
+                self.mesh_lookup.insert(key, self.meshes.len() - 1);
+                self.meshes.len() - 1
+            }
+        };
+
+        let mesh = &mut self.meshes[mesh_index];
+        mesh.has_uvs &= has_uvs;
+        mesh.has_normals &= has_normals;
+
+        // Polygons are split into a fan of triangles around the first vertex.
+        for i in 1..(vertices.len() - 1) {
+            mesh.indices
+                .push([vertices[0], vertices[i], vertices[i + 1]]);
+        }
+
+        Ok(())
+    }
+
+    /// Parse an entire OBJ file.
+    fn parse(path: &Path) -> Result<Self, ImportError> {
+        let file = File::open(path).map_err(|error| ImportError::io(path, error))?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        let mut data = ObjData::default();
+        let mut group = String::new();
+        let mut material: Option<String> = None;
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line_no = index + 1;
+            let line = line.map_err(|error| ImportError::io(path, error))?;
+
+            // Everything after a `#` is a comment.
+            let line = line.split('#').next().unwrap_or_default();
+            let mut parts = line.split_whitespace();
+
+            let Some(keyword) = parts.next() else {
+                continue;
+            };
+            let args: Vec<&str> = parts.collect();
+
+            match keyword {
+                "v" => data.positions.push(point3!(
+                    parse_float(path, line_no, &args, 0)?,
+                    parse_float(path, line_no, &args, 1)?,
+                    parse_float(path, line_no, &args, 2)?
+                )),
+                "vt" => data.uvs.push((
+                    parse_float(path, line_no, &args, 0)?,
+                    match args.len() {
+                        1 => 0.0,
+                        _ => parse_float(path, line_no, &args, 1)?,
+                    },
+                )),
+                "vn" => data.normals.push(vec3!(
+                    parse_float(path, line_no, &args, 0)?,
+                    parse_float(path, line_no, &args, 1)?,
+                    parse_float(path, line_no, &args, 2)?
+                )),
+                "f" => data.add_face(path, line_no, &args, &group, &material)?,
+                "g" | "o" => group = args.join(" "),
+                "usemtl" => material = Some(args.join(" ")),
+                "mtllib" => {
+                    for file in args {
+                        data.materials.extend(load_mtl(&directory.join(file))?);
+                    }
+                }
+                // Lines, points, smoothing groups etc. don't mean anything to a raytracer.
+                _ => {}
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Turn the parsed data into meshes.
+    fn into_meshes(self) -> Vec<TriangleMesh> {
+        let positions = Arc::new(self.vertex_positions);
+        let uvs = Arc::new(self.vertex_uvs);
+        let normals = Arc::new(self.vertex_normals);
+
+        // Faces without a (known) material get a plain grey one.
+        let default_material: Arc<dyn Material + Sync + Send> =
+            Arc::new(Lambertian::new(color!(0.8, 0.8, 0.8)));
+
+        self.meshes
+            .into_iter()
+            .map(|mesh| {
+                let material = mesh
+                    .material
+                    .and_then(|name| self.materials.get(&name).cloned())
+                    .unwrap_or_else(|| default_material.clone());
+
+                TriangleMesh::new(
+                    positions.clone(),
+                    mesh.has_normals.then(|| normals.clone()),
+                    mesh.has_uvs.then(|| uvs.clone()),
+                    mesh.indices,
+                    material,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Load an OBJ file (and the MTL files it refers to) into a new scene.
+pub fn load_obj<P: AsRef<Path>>(path: P) -> Result<Scene, ImportError> {
+    let mut scene = Scene::new();
+    load_obj_into(path, &mut scene)?;
+    Ok(scene)
+}
+
+/// Load an OBJ file (and the MTL files it refers to), adding its meshes to an existing scene.
+pub fn load_obj_into<P: AsRef<Path>>(path: P, scene: &mut Scene) -> Result<(), ImportError> {
+    let data = ObjData::parse(path.as_ref())?;
+
+    for mesh in data.into_meshes() {
+        scene.add(Box::new(mesh));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::PathBuf};
+
+    /// Write the files into a directory of their own (in the temp directory), returning the path of the first.
+    fn write_files(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("raytracing-{}-{}", std::process::id(), test));
+        fs::create_dir_all(&directory).unwrap();
+
+        for (name, contents) in files {
+            fs::write(directory.join(name), contents).unwrap();
+        }
+
+        directory.join(files[0].0)
+    }
+
+    /// The line an OBJ (or MTL) file fails to parse on, and the file it's in.
+    fn error_line(test: &str, files: &[(&str, &str)]) -> (String, usize) {
+        match ObjData::parse(&write_files(test, files)) {
+            Err(ImportError::Parse { path, line, .. }) => (
+                path.file_name().unwrap().to_string_lossy().into_owned(),
+                line,
+            ),
+            result => panic!("Expected a parse error, got {:?}", result.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn parses_faces_groups_and_materials() {
+        let path = write_files(
+            "valid",
+            &[
+                (
+                    "model.obj",
+                    "mtllib model.mtl\n\
+                     v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0 # comment\n\
+                     vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+                     usemtl red\n\
+                     f 1/1 2/2 3/3 4/4\n\
+                     g other\n\
+                     f -4 -3 -2\n",
+                ),
+                ("model.mtl", "newmtl red\nKd 1 0 0\n"),
+            ],
+        );
+        let data = ObjData::parse(&path).unwrap();
+
+        assert!(data.materials.contains_key("red"));
+        assert_eq!(data.meshes.len(), 2);
+
+        // The quad is split into two triangles, which share the corners they have in common.
+        assert_eq!(data.meshes[0].indices, vec![[0, 1, 2], [0, 2, 3]]);
+        assert!(data.meshes[0].has_uvs && !data.meshes[0].has_normals);
+        assert_eq!(data.meshes[0].material.as_deref(), Some("red"));
+
+        // Without UVs, the same positions are different vertices.
+        assert_eq!(data.meshes[1].indices, vec![[4, 5, 6]]);
+        assert!(!data.meshes[1].has_uvs);
+        assert_eq!(data.vertex_positions.len(), 7);
+
+        assert!(load_obj(&path).is_ok());
+    }
+
+    #[test]
+    fn reports_the_line_of_obj_errors() {
+        let obj = |name: &str, contents: &str| error_line(name, &[("model.obj", contents)]);
+        let expected = ("model.obj".to_string(), 3);
+
+        assert_eq!(obj("float", "v 0 0 0\nv 1 0 0\nv 1 zero 0\n"), expected);
+        assert_eq!(obj("short", "v 0 0 0\n\nv 1 0\n"), expected);
+        assert_eq!(obj("face", "v 0 0 0\nv 1 0 0\nf 1 2\n"), expected);
+        assert_eq!(obj("bounds", "v 0 0 0\nv 1 0 0\nf 1 2 3\n"), expected);
+        assert_eq!(obj("zero", "v 0 0 0\n# comment\nf 0 1 1\n"), expected);
+        assert_eq!(obj("negative", "v 0 0 0\nv 1 0 0\nf -1 -2 -3\n"), expected);
+        assert_eq!(obj("corner", "v 0 0 0\nvt 0 0\nf 1/1/1/1 1 1\n"), expected);
+    }
+
+    #[test]
+    fn reports_the_line_of_mtl_errors() {
+        let mtl = |name: &str, contents: &str| {
+            error_line(
+                name,
+                &[
+                    ("model.obj", "# materials\nmtllib model.mtl\n"),
+                    ("model.mtl", contents),
+                ],
+            )
+        };
+        let expected = ("model.mtl".to_string(), 2);
+
+        assert_eq!(mtl("before", "# no material yet\nKd 1 1 1\n"), expected);
+        assert_eq!(mtl("name", "Kd 1 1 1\n"), ("model.mtl".to_string(), 1));
+        assert_eq!(mtl("unnamed", "newmtl a\nnewmtl\n"), expected);
+        assert_eq!(mtl("color", "newmtl a\nKd 1 one 1\n"), expected);
+        assert_eq!(mtl("illum", "newmtl a\nillum two\n"), expected);
+    }
+
+    #[test]
+    fn missing_files_are_io_errors() {
+        let path = write_files("missing", &[("model.obj", "mtllib nowhere.mtl\n")]);
+
+        assert!(matches!(
+            ObjData::parse(&path),
+            Err(ImportError::Io { path, .. }) if path.ends_with("nowhere.mtl")
+        ));
+        assert!(matches!(
+            load_obj(path.with_file_name("nowhere.obj")),
+            Err(ImportError::Io { .. })
+        ));
+    }
+}
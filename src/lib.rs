@@ -1,66 +1,123 @@
 pub mod camera;
+pub mod error;
 pub mod file;
+pub mod import;
+pub mod lights;
 pub mod materials;
 pub mod objects;
 pub mod structs;
+pub mod textures;
 
-use camera::Camera;
-use file::FileWriter;
-use std::sync::OnceLock;
+use camera::{Camera, Projection};
+use error::Error;
+use file::{FileWriter, ToneMap};
+use std::sync::Arc;
 use structs::{Point3, Scene, Vec3};
 
 /// A struct for the caller to pass all user-defined arguments.
-#[derive(Debug)]
+///
+/// The scene is behind an `Arc` so the same scene can be shared across many renders
+/// (eg. a turntable, where only `look_from` changes between frames).
+#[derive(Debug, Clone)]
 pub struct Options {
-    pub scene: Scene,
+    pub scene: Arc<Scene>,
     pub width: u16,
     pub height: u16,
-    pub fov: FOV,
+    /// The width of a pixel divided by its height.
+    /// 1.0 for square pixels, more for anamorphic and some broadcast formats, which are stretched
+    /// horizontally when displayed.
+    pub pixel_aspect_ratio: f64,
+    /// The physical size of the camera's film (or sensor).
+    pub film_back: FilmBack,
+    /// How the camera maps its film to the scene (eg. `Perspective` for the usual camera).
+    pub projection: Arc<dyn Projection + Sync + Send>,
     pub look_from: Point3,
     pub look_to: Point3,
     pub vup: Vec3,
     pub max_bounces: u8,
     pub samples: u16,
     pub shutter_open_duration: f64,
+    /// How wide the lens opens, deciding how blurry things away from the focus distance get.
+    pub aperture: Aperture,
+    /// The shape of the lens opening, which out-of-focus highlights (bokeh) take on.
+    pub aperture_shape: ApertureShape,
+    /// Distance from `look_from` to the plane that is perfectly in focus.
+    /// Defaults to the distance between `look_from` and `look_to`.
+    pub focus_distance: Option<f64>,
+    /// Exposure adjustment in stops, applied before tone mapping.
+    pub exposure: f64,
+    /// How radiance brighter than white is brought into the displayable range.
+    pub tone_map: ToneMap,
+    /// Trace light as a few wavelengths at a time instead of RGB, which is slower, but gets light that isn't
+    /// a smooth mix of colors right (see `structs::Spectrum`), like how a fluorescent light
+    /// makes some colors look off, and glass splitting light into a rainbow.
+    pub spectral: bool,
 }
 
 /// An enum for passing field-of-view in degrees in any axis we want.
 /// The other axis would be calculated based on the aspect ratio, which in turn
-/// is calculated from `WIDTH` and `HEIGHT`.
+/// is calculated from `width`, `height` and `pixel_aspect_ratio`.
 #[derive(Debug, Copy, Clone)]
 pub enum FOV {
     Vertical(f64),
     Horizontal(f64),
+    /// The focal length of the lens in millimeters, as on a real camera.
+    /// The field-of-view then depends on the film back.
+    FocalLength(f64),
 }
 
-// These constants are loaded in `run()` and used throughout the program.
-// OnceCell allows us to let the caller to `run()` pass the variables,
-// but still treat them as constants (makes multithreading simpler),
-// and allows us to do benchmarks.
-
-// Width and height of the rendered image.
-// The dimensions of the viewport are calculated using field-of-view data.
-static WIDTH: OnceLock<u16> = OnceLock::new();
-static HEIGHT: OnceLock<u16> = OnceLock::new();
-
-/// The scene itself.
-static SCENE: OnceLock<Scene> = OnceLock::new();
-
-/// Max. no of bounces a ray can have before it just turns black.
-static MAX_BOUNCES: OnceLock<u8> = OnceLock::new();
-/// Max. no of samples. More samples give a more "smooth" look but are more compute-intensive.
-static SAMPLES: OnceLock<u16> = OnceLock::new();
-
-/// The camera's assumed center.
-static LOOK_FROM: OnceLock<Point3> = OnceLock::new();
-/// The point the camera is looking at.
-static LOOK_TO: OnceLock<Point3> = OnceLock::new();
-/// What direction is up, usually case positive y-axis.
-static VUP: OnceLock<Vec3> = OnceLock::new();
-/// Vertical or horizontal field of view in degrees.
-static FIELD_OF_VIEW: OnceLock<FOV> = OnceLock::new();
-/// The duration the camera's shutter is open (for motion blur);
-static SHUTTER_OPEN_DURATION: OnceLock<f64> = OnceLock::new();
+/// The physical size of a camera's film (or sensor), in millimeters.
+///
+/// When the image has a different aspect ratio than the film, it's the largest part of the film
+/// (around its center) with the image's aspect ratio that gets rendered, like a real camera cropping its sensor.
+#[derive(Debug, Copy, Clone)]
+pub struct FilmBack {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl FilmBack {
+    /// 35mm still film, and full-frame digital cameras.
+    pub const FULL_FRAME: FilmBack = FilmBack::new(36.0, 24.0);
+    /// Super 35 motion picture film.
+    pub const SUPER_35: FilmBack = FilmBack::new(24.89, 18.66);
+    /// The usual size of a crop-sensor (APS-C) digital camera.
+    pub const APS_C: FilmBack = FilmBack::new(23.6, 15.7);
+    pub const MICRO_FOUR_THIRDS: FilmBack = FilmBack::new(17.3, 13.0);
+    /// 2/3" sensors, as used in broadcast cameras.
+    pub const TWO_THIRDS_INCH: FilmBack = FilmBack::new(9.59, 5.39);
+    /// 35mm film's 4-perf area used for anamorphic lenses (which squeeze the image twice as much horizontally).
+    pub const ANAMORPHIC_35: FilmBack = FilmBack::new(21.95, 18.6);
+
+    pub const fn new(width: f64, height: f64) -> Self {
+        FilmBack { width, height }
+    }
+}
+
+/// The size of the camera's lens opening.
+#[derive(Debug, Copy, Clone)]
+pub enum Aperture {
+    /// An infinitely small opening, so everything is in focus.
+    Pinhole,
+    /// The angle (in degrees) of the cone of rays from the lens to each point on the focus plane.
+    DefocusAngle(f64),
+    /// The f-number, ie. the lens' focal length divided by the opening's diameter, as on a real camera.
+    /// The focal length is the one giving the same field-of-view with the film back,
+    /// with the scene in meters.
+    FStop(f64),
+}
+
+/// The shape of the camera's lens opening.
+#[derive(Debug, Copy, Clone)]
+pub enum ApertureShape {
+    Circle,
+    /// A regular polygon, as formed by the blades of a real lens' diaphragm.
+    /// The rotation is in degrees.
+    Polygon {
+        blades: u8,
+        rotation: f64,
+    },
+}
 
 //? A really good but compute-heavy scene.
 //todo! examples/ with this scene
@@ -70,7 +127,7 @@ static SHUTTER_OPEN_DURATION: OnceLock<f64> = OnceLock::new();
     scene.add(Box::new(Sphere::new(
         point3!(0, -1000, 0),
         1000,
-        Arc::new(Lambertian::new(color!(130, 130, 130))),
+        Arc::new(Lambertian::new(color!(0.5, 0.5, 0.5))),
     )));
 
     for i in -8..=8 {
@@ -102,7 +159,7 @@ static SHUTTER_OPEN_DURATION: OnceLock<f64> = OnceLock::new();
     scene.add(Box::new(Sphere::new(
         point3!(-4, 1, 0),
         1.0,
-        Arc::new(Lambertian::new(color!(180, 77, 77))),
+        Arc::new(Lambertian::new(color!(0.7, 0.3, 0.3))),
     )));
     scene.add(Box::new(Sphere::new(
         point3!(0, 1, 0),
@@ -112,29 +169,20 @@ static SHUTTER_OPEN_DURATION: OnceLock<f64> = OnceLock::new();
     scene.add(Box::new(Sphere::new(
         point3!(4, 1, 0),
         1.0,
-        Arc::new(Metal::new(color!(178, 153, 127), 0.0)),
+        Arc::new(Metal::new(color!(0.7, 0.6, 0.5), 0.0)),
     )));
 
     scene
 }); */
 
-pub fn run(opts: Options, file_writer: &mut dyn FileWriter) {
-    // Initialize the OnceCell statics.
-    // These shouldn't fail (hopefully).
-    WIDTH.get_or_init(|| opts.width);
-    HEIGHT.get_or_init(|| opts.height);
-    FIELD_OF_VIEW.get_or_init(|| opts.fov);
-    MAX_BOUNCES.get_or_init(|| opts.max_bounces);
-    SAMPLES.get_or_init(|| opts.samples);
-    LOOK_FROM.get_or_init(|| opts.look_from);
-    LOOK_TO.get_or_init(|| opts.look_to);
-    VUP.get_or_init(|| opts.vup);
-    SCENE.get_or_init(|| opts.scene);
-    SHUTTER_OPEN_DURATION.get_or_init(|| opts.shutter_open_duration);
-
+/// Render the scene described by `opts` into `file_writer`.
+///
+/// This is a shorthand for building a `Camera` and rendering once. Callers that want to render
+/// several times (or several renders at once) can build and keep `Camera`s themselves.
+pub fn run(opts: Options, file_writer: &mut dyn FileWriter) -> Result<(), Error> {
     // Init camera
-    let camera = Camera::new();
+    let camera = Camera::new(&opts);
 
     // Render ahoy!
-    camera.render(file_writer);
+    camera.render(file_writer)
 }
@@ -0,0 +1,80 @@
+use super::{cone_solid_angle, random_in_cone, Light, LightHit, LightSample};
+use crate::structs::{Interval, Point3, Ray, Spectrum, Vec3};
+
+/// A light infinitely far away, shining in one direction over the entire scene, like the sun.
+///
+/// `intensity` is how much light falls on a surface facing it. `angular_diameter` is how big the light looks
+/// (in degrees, about 0.53 for the sun), which softens shadows; with 0, shadows are perfectly sharp
+/// and the light doesn't show up in the image.
+#[derive(Debug)]
+pub struct DirectionalLight {
+    /// Unit vector from the scene towards the light (against the way it shines).
+    towards: Vec3,
+    color: Spectrum,
+    intensity: f64,
+    /// Cosine of the angle between the middle and the edge of the light.
+    cos_radius: f64,
+}
+
+impl DirectionalLight {
+    /// `direction` is the way the light shines (eg. downwards for the sun at noon).
+    pub fn new<C: Into<Spectrum>, T: Into<f64>, A: Into<f64>>(
+        direction: Vec3,
+        color: C,
+        intensity: T,
+        angular_diameter: A,
+    ) -> Self {
+        DirectionalLight {
+            towards: -direction.unit_vec(),
+            color: color.into(),
+            intensity: intensity.into(),
+            cos_radius: (angular_diameter.into() / 2.0).to_radians().cos(),
+        }
+    }
+
+    fn is_point(&self) -> bool {
+        self.cos_radius >= 1.0
+    }
+
+    /// The light coming from every direction within the disc, so it all adds up to `intensity`
+    /// (ignoring that the edges of the disc hit at a slightly different angle).
+    fn radiance(&self) -> Spectrum {
+        self.color.clone() * (self.intensity / cone_solid_angle(self.cos_radius))
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _: Point3) -> Option<LightSample> {
+        let sample = match self.is_point() {
+            true => LightSample {
+                direction: self.towards,
+                distance: f64::INFINITY,
+                radiance: self.color.clone() * self.intensity,
+                pdf: None,
+            },
+            false => LightSample {
+                direction: random_in_cone(self.towards, self.cos_radius),
+                distance: f64::INFINITY,
+                radiance: self.radiance(),
+                pdf: Some(1.0 / cone_solid_angle(self.cos_radius)),
+            },
+        };
+
+        Some(sample)
+    }
+
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<LightHit> {
+        if self.is_point()
+            || interval.max < f64::INFINITY
+            || ray.direction().unit_vec().dot(self.towards) < self.cos_radius
+        {
+            return None;
+        }
+
+        Some(LightHit {
+            time: f64::INFINITY,
+            radiance: self.radiance(),
+            pdf: 1.0 / cone_solid_angle(self.cos_radius),
+        })
+    }
+}
@@ -0,0 +1,91 @@
+//! Lights that aren't part of the scene's geometry, which the camera looks for directly (see `Scene::add_light()`).
+//!
+//! Emissive materials (see `DiffuseLight`) only light up the scene when rays happen to bounce into them,
+//! which gets very noisy for small lights. These lights are instead sampled from every surface that
+//! scatters light all over, with a shadow ray to check if anything is in the way.
+
+use crate::{
+    structs::{Interval, Point3, Ray, Spectrum, Vec3},
+    vec3,
+};
+use rand::Rng;
+use std::f64::consts::PI;
+
+mod directional;
+mod point;
+mod rect;
+mod sphere;
+mod spot;
+
+pub use directional::DirectionalLight;
+pub use point::PointLight;
+pub use rect::RectLight;
+pub use sphere::SphereLight;
+pub use spot::SpotLight;
+
+/// The light reaching a point from a light, as picked by `Light::sample()`.
+#[derive(Debug, Clone)]
+pub struct LightSample {
+    /// Unit vector from the point towards the light.
+    pub direction: Vec3,
+    /// How far away the light is, in the direction. (Infinite for directional lights)
+    pub distance: f64,
+    /// The light arriving at the point.
+    pub radiance: Spectrum,
+    /// The pdf (over directions) of picking this direction,
+    /// or `None` for lights coming from a single point or direction, which rays can never hit.
+    pub pdf: Option<f64>,
+}
+
+/// Where a ray runs into a light, as found by `Light::hit()`.
+#[derive(Debug, Clone)]
+pub struct LightHit {
+    /// How far along the ray the light is, like `HitData::time()`. (Infinite for directional lights)
+    pub time: f64,
+    /// The light the ray sees.
+    pub radiance: Spectrum,
+    /// The pdf of `Light::sample()` picking the ray's direction from its origin.
+    pub pdf: f64,
+}
+
+pub trait Light: std::fmt::Debug {
+    /// Pick a direction from `point` towards the light, with how much light comes from it.
+    ///
+    /// `None` if the light can't reach the point at all (eg. behind a spot light).
+    fn sample(&self, point: Point3) -> Option<LightSample>;
+
+    /// Check if a ray runs into the light.
+    ///
+    /// Lights coming from a single point or direction can't be hit, which is the default.
+    fn hit(&self, _ray: Ray, _interval: Interval) -> Option<LightHit> {
+        None
+    }
+}
+
+/// A random unit vector within the cone around `axis` (a unit vector) where the cosine with it is at least `cos_max`,
+/// equally likely to point anywhere in it.
+///
+/// The pdf of every direction is `1 / cone_solid_angle(cos_max)`.
+fn random_in_cone(axis: Vec3, cos_max: f64) -> Vec3 {
+    let mut rng = rand::thread_rng();
+
+    // The cosine is uniform between 1 and `cos_max`, which spreads directions evenly over the cap of the sphere.
+    let cos = 1.0 - rng.gen_range(0.0..1.0) * (1.0 - cos_max);
+    let sin = (1.0 - cos * cos).max(0.0).sqrt();
+    let angle = rng.gen_range(0.0..(2.0 * PI));
+
+    // Any two vectors perpendicular to the axis (and each other) to go around it.
+    let helper = match axis.x().abs() > 0.9 {
+        true => vec3!(0, 1, 0),
+        false => vec3!(1, 0, 0),
+    };
+    let tangent = axis.cross(helper).unit_vec();
+    let bitangent = axis.cross(tangent);
+
+    axis * cos + (tangent * angle.cos() + bitangent * angle.sin()) * sin
+}
+
+/// The solid angle of a cone, where the cosine with its axis is at least `cos_max`.
+fn cone_solid_angle(cos_max: f64) -> f64 {
+    2.0 * PI * (1.0 - cos_max)
+}
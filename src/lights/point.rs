@@ -0,0 +1,37 @@
+use super::{Light, LightSample};
+use crate::structs::{Point3, Spectrum};
+
+/// A light giving off the same amount of light in every direction from a single point.
+///
+/// The light falls off with the square of the distance, so `intensity` is how bright it is 1 unit away.
+/// It has no size, so shadows are perfectly sharp and it never shows up in the image (or reflections).
+#[derive(Debug)]
+pub struct PointLight {
+    position: Point3,
+    color: Spectrum,
+    intensity: f64,
+}
+
+impl PointLight {
+    pub fn new<C: Into<Spectrum>, T: Into<f64>>(position: Point3, color: C, intensity: T) -> Self {
+        PointLight {
+            position,
+            color: color.into(),
+            intensity: intensity.into(),
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, point: Point3) -> Option<LightSample> {
+        let to_light = self.position - point;
+        let distance = to_light.length();
+
+        Some(LightSample {
+            direction: to_light / distance,
+            distance,
+            radiance: self.color.clone() * (self.intensity / (distance * distance)),
+            pdf: None,
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use super::{Light, LightHit, LightSample};
+use crate::structs::{Interval, Point3, Ray, Spectrum, Vec3};
+use rand::Rng;
+
+/// A glowing rectangle, like a softbox or a window.
+///
+/// The rectangle has a `corner` and two edges going out of it, and only gives off light on the side that
+/// `edge_u × edge_v` points to (counter-clockwise going from `edge_u` to `edge_v`, seen from the front).
+/// `intensity` is how bright its surface looks, so bigger lights give off more light in total.
+#[derive(Debug)]
+pub struct RectLight {
+    corner: Point3,
+    edge_u: Vec3,
+    edge_v: Vec3,
+    /// Unit vector out of the front of the rectangle.
+    normal: Vec3,
+    area: f64,
+    color: Spectrum,
+    intensity: f64,
+}
+
+impl RectLight {
+    pub fn new<C: Into<Spectrum>, T: Into<f64>>(
+        corner: Point3,
+        edge_u: Vec3,
+        edge_v: Vec3,
+        color: C,
+        intensity: T,
+    ) -> Self {
+        let cross = edge_u.cross(edge_v);
+
+        RectLight {
+            corner,
+            edge_u,
+            edge_v,
+            normal: cross.unit_vec(),
+            area: cross.length(),
+            color: color.into(),
+            intensity: intensity.into(),
+        }
+    }
+
+    /// Turn the pdf of picking a point on the rectangle (by its area) into one over directions,
+    /// as seen `distance` away at an angle to the rectangle (given as its cosine).
+    fn pdf(&self, distance: f64, cos: f64) -> f64 {
+        distance * distance / (self.area * cos)
+    }
+}
+
+impl Light for RectLight {
+    /// Picks a point anywhere on the rectangle.
+    fn sample(&self, point: Point3) -> Option<LightSample> {
+        let mut rng = rand::thread_rng();
+        let on_light = self.corner
+            + self.edge_u * rng.gen_range(0.0..1.0)
+            + self.edge_v * rng.gen_range(0.0..1.0);
+
+        let to_light = on_light - point;
+        let distance = to_light.length();
+        let direction = to_light / distance;
+
+        // The point is behind the rectangle.
+        let cos = -direction.dot(self.normal);
+        if cos <= 0.0 {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: self.color.clone() * self.intensity,
+            pdf: Some(self.pdf(distance, cos)),
+        })
+    }
+
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<LightHit> {
+        // Only the front gives off light (and the back is invisible, so rays go right through it).
+        let facing = -ray.direction().dot(self.normal);
+        if facing <= 0.0 {
+            return None;
+        }
+
+        // Where the ray meets the plane of the rectangle.
+        let time = (self.corner - ray.origin()).dot(self.normal) / -facing;
+        if !interval.surrounds(time) {
+            return None;
+        }
+
+        // How far along each edge that is.
+        // https://raytracing.github.io/books/RayTracingTheNextWeek.html#quadrilaterals/derivingtheplanarcoordinates
+        let offset = ray.at(time) - self.corner;
+        let cross = self.edge_u.cross(self.edge_v);
+        let w = cross / cross.length_squared();
+        let alpha = w.dot(offset.cross(self.edge_v));
+        let beta = w.dot(self.edge_u.cross(offset));
+
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let distance = time * ray.direction().length();
+        let cos = facing / ray.direction().length();
+
+        Some(LightHit {
+            time,
+            radiance: self.color.clone() * self.intensity,
+            pdf: self.pdf(distance, cos),
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use super::{cone_solid_angle, random_in_cone, Light, LightHit, LightSample};
+use crate::structs::{Interval, Point3, Ray, Spectrum};
+
+/// A glowing ball, giving off light evenly from all of its surface, like a light bulb.
+///
+/// `intensity` is how bright its surface looks, so bigger lights give off more light in total.
+#[derive(Debug)]
+pub struct SphereLight {
+    center: Point3,
+    radius: f64,
+    color: Spectrum,
+    intensity: f64,
+}
+
+impl SphereLight {
+    pub fn new<C: Into<Spectrum>, R: Into<f64>, T: Into<f64>>(
+        center: Point3,
+        radius: R,
+        color: C,
+        intensity: T,
+    ) -> Self {
+        SphereLight {
+            center,
+            radius: radius.into().abs(),
+            color: color.into(),
+            intensity: intensity.into(),
+        }
+    }
+
+    /// Cosine of the angle between the middle and the edge of the sphere, as seen from `point`.
+    /// `None` if the point is inside it.
+    fn cos_radius(&self, point: Point3) -> Option<f64> {
+        let distance_squared = (self.center - point).length_squared();
+        let sin_squared = self.radius * self.radius / distance_squared;
+
+        match sin_squared < 1.0 {
+            true => Some((1.0 - sin_squared).sqrt()),
+            false => None,
+        }
+    }
+
+    /// Where a ray first hits the sphere, within `interval`.
+    fn intersect(&self, ray: Ray, interval: Interval) -> Option<f64> {
+        let distance = ray.origin() - self.center;
+
+        let a = ray.direction().length_squared();
+        let b = distance.dot(ray.direction());
+        let c = distance.length_squared() - self.radius * self.radius;
+        let discriminant = b * b - a * c;
+
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        [
+            (-b - discriminant.sqrt()) / a,
+            (-b + discriminant.sqrt()) / a,
+        ]
+        .into_iter()
+        .find(|&root| interval.surrounds(root))
+    }
+}
+
+impl Light for SphereLight {
+    /// Only picks directions to the side of the sphere facing the point, by picking them within
+    /// the cone it covers as seen from the point.
+    fn sample(&self, point: Point3) -> Option<LightSample> {
+        let cos_radius = self.cos_radius(point)?;
+        let direction = random_in_cone((self.center - point).unit_vec(), cos_radius);
+
+        // The cone just touches the sphere at its edges, so make sure the rounding doesn't miss it.
+        let distance = self
+            .intersect(Ray::new(point, direction), Interval::new(0, f64::INFINITY))
+            .unwrap_or_else(|| (self.center - point).length());
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: self.color.clone() * self.intensity,
+            pdf: Some(1.0 / cone_solid_angle(cos_radius)),
+        })
+    }
+
+    fn hit(&self, ray: Ray, interval: Interval) -> Option<LightHit> {
+        let time = self.intersect(ray, interval)?;
+        let cos_radius = self.cos_radius(ray.origin())?;
+
+        Some(LightHit {
+            time,
+            radiance: self.color.clone() * self.intensity,
+            pdf: 1.0 / cone_solid_angle(cos_radius),
+        })
+    }
+}
@@ -0,0 +1,75 @@
+use super::{Light, LightSample};
+use crate::structs::{Point3, Spectrum, Vec3};
+
+/// A point light (see `PointLight`) that only shines within a cone, like a stage light or a flashlight.
+///
+/// `cone_angle` is the angle (in degrees) across the entire cone, and the light fades out smoothly over
+/// the outer `falloff_angle` degrees of it (on each side), so 0 gives a hard edge.
+#[derive(Debug)]
+pub struct SpotLight {
+    position: Point3,
+    /// Unit vector the cone points in.
+    direction: Vec3,
+    color: Spectrum,
+    intensity: f64,
+    /// Cosines of the angles from the middle of the cone where the light starts fading, and where it's gone.
+    cos_inner: f64,
+    cos_outer: f64,
+}
+
+impl SpotLight {
+    pub fn new<C: Into<Spectrum>, T: Into<f64>, A: Into<f64>, F: Into<f64>>(
+        position: Point3,
+        direction: Vec3,
+        color: C,
+        intensity: T,
+        cone_angle: A,
+        falloff_angle: F,
+    ) -> Self {
+        let outer = cone_angle.into() / 2.0;
+        let inner = (outer - falloff_angle.into()).max(0.0);
+
+        SpotLight {
+            position,
+            direction: direction.unit_vec(),
+            color: color.into(),
+            intensity: intensity.into(),
+            cos_inner: inner.to_radians().cos(),
+            cos_outer: outer.to_radians().cos(),
+        }
+    }
+
+    /// How much of the light goes out at an angle (given as its cosine) from the middle of the cone.
+    fn falloff(&self, cos: f64) -> f64 {
+        match cos >= self.cos_inner {
+            true => 1.0,
+            false => {
+                let t =
+                    ((cos - self.cos_outer) / (self.cos_inner - self.cos_outer)).clamp(0.0, 1.0);
+
+                // Smoothstep, so there's no visible edge where the fading starts.
+                t * t * (3.0 - 2.0 * t)
+            }
+        }
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, point: Point3) -> Option<LightSample> {
+        let to_light = self.position - point;
+        let distance = to_light.length();
+        let direction = to_light / distance;
+
+        let falloff = self.falloff(-direction.dot(self.direction));
+        if falloff <= 0.0 {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: self.color.clone() * (self.intensity * falloff / (distance * distance)),
+            pdf: None,
+        })
+    }
+}
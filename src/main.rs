@@ -1,13 +1,16 @@
 use raytracing::{
-    self, color,
-    file::PPMFile,
+    self,
+    camera::Perspective,
+    color,
+    error::Error,
+    file::{ExrFile, FileWriter, HdrFile, PPMFile, PfmFile, PngFile, ToneMap},
     materials::{Dielectric, Lambertian, Metal},
     objects::Sphere,
     point3,
     structs::Scene,
-    vec3, Options, FOV,
+    vec3, Aperture, ApertureShape, FilmBack, Options, FOV,
 };
-use std::{sync::Arc, time::Instant};
+use std::{env, path::Path, process, sync::Arc, time::Instant};
 
 fn main() {
     let start_time = Instant::now();
@@ -19,13 +22,13 @@ fn main() {
         scene.add(Box::new(Sphere::new(
             point3!(0, -100.5, -1),
             100,
-            Arc::new(Lambertian::new(color!(205, 205, 0))),
+            Arc::new(Lambertian::new(color!(0.8, 0.8, 0.0))),
             vec3!(0, 0, 0),
         )));
         scene.add(Box::new(Sphere::new(
             point3!(0, 0, -1),
             0.5,
-            Arc::new(Lambertian::new(color!(180, 77, 77))),
+            Arc::new(Lambertian::new(color!(0.7, 0.3, 0.3))),
             vec3!(0, 3, 0),
         )));
         scene.add(Box::new(Sphere::new(
@@ -37,7 +40,7 @@ fn main() {
         scene.add(Box::new(Sphere::new(
             point3!(1, 0, -1),
             0.5,
-            Arc::new(Metal::new(color!(204, 204, 204), 0.1)),
+            Arc::new(Metal::new(color!(0.8, 0.8, 0.8), 0.1)),
             vec3!(0, 0, 0),
         )));
 
@@ -45,23 +48,63 @@ fn main() {
     };
 
     let opts = Options {
-        scene,
+        scene: Arc::new(scene),
         width: 800,
         height: 400,
-        fov: FOV::Vertical(50.0),
+        pixel_aspect_ratio: 1.0,
+        film_back: FilmBack::FULL_FRAME,
+        projection: Arc::new(Perspective::new(FOV::Vertical(50.0))),
         look_from: point3!(0.0, 0.0, 1.0),
         look_to: point3!(0.0, 0.0, 0.0),
         vup: vec3!(0.0, 1.0, 0.0),
         max_bounces: 20,
         samples: 20,
         shutter_open_duration: 1.0 / 24.0, // 24 FPS
+        aperture: Aperture::Pinhole,
+        aperture_shape: ApertureShape::Circle,
+        focus_distance: None,
+        exposure: 0.0,
+        tone_map: ToneMap::Clamp,
+        spectral: false,
     };
 
-    let mut file = PPMFile::new("output.ppm", 800, 400);
+    // The output file can be passed as the first argument, and its extension decides the format.
+    let output = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "output.ppm".to_string());
 
-    println!("Starting raytracer... ");
-
-    raytracing::run(opts, &mut file);
+    if let Err(error) = render(opts, &output) {
+        eprintln!("Error: {}", error);
+        process::exit(1);
+    }
 
     println!("Finished in {}s", start_time.elapsed().as_secs());
 }
+
+/// Render into the `output` file, picking the format from its extension.
+fn render(opts: Options, output: &str) -> Result<(), Error> {
+    let mut file: Box<dyn FileWriter> =
+        match Path::new(output).extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => Box::new(PPMFile::new(output)?),
+            Some("png") => Box::new(
+                PngFile::new(output)?
+                    .text("Samples", opts.samples.to_string())
+                    .text("Max Bounces", opts.max_bounces.to_string())
+                    .text(
+                        "Camera",
+                        format!(
+                            "{:?} from {:?} to {:?}",
+                            opts.projection, opts.look_from, opts.look_to
+                        ),
+                    ),
+            ),
+            Some("exr") => Box::new(ExrFile::new(output)?),
+            Some("hdr") => Box::new(HdrFile::new(output)?),
+            Some("pfm") => Box::new(PfmFile::new(output)?),
+            _ => return Err(Error::UnsupportedFormat(output.to_string())),
+        };
+
+    println!("Starting raytracer... ");
+
+    raytracing::run(opts, file.as_mut())
+}
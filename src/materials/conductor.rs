@@ -0,0 +1,215 @@
+use super::{commons::reflect, microfacet::Ggx, Lobe, Material, Scatter};
+use crate::{
+    color,
+    structs::{Color, Frame, HitData, Ray, Vec3},
+};
+use rand::Rng;
+
+/// Structure representing a physically based metal.
+///
+/// The surface is made of tiny mirror-like facets (see `microfacet.rs`), spread out by `roughness` (0 - 1),
+/// so 0 is a perfect mirror and 1 is almost matte. How much light each facet reflects depends on the
+/// angle and the metal's complex index of refraction (`eta` + i`k`, for red, green and blue),
+/// which gives metals their color, and makes them brighter and whiter towards the edges.
+///
+/// See the presets (eg. `Conductor::gold()`) for real metals, with values from
+/// https://refractiveindex.info averaged over each color.
+///
+/// Brushed metals are rougher across the brushing than along it, see `anisotropic()`.
+#[derive(Debug)]
+pub struct Conductor {
+    eta: Color,
+    k: Color,
+    distribution: Ggx,
+    /// How far the direction of the roughness is turned from the surface's tangent, in radians.
+    rotation: f64,
+}
+
+impl Conductor {
+    pub fn new<T: Into<f64>>(eta: Color, k: Color, roughness: T) -> Self {
+        let roughness = roughness.into().clamp(0.0, 1.0);
+
+        Conductor {
+            eta,
+            k,
+            distribution: Ggx::from_roughness(roughness, roughness),
+            rotation: 0.0,
+        }
+    }
+
+    /// Separate roughnesses (0 - 1) along the surface's `u` and `v` directions (see `HitData::frame()`),
+    /// instead of the same all around, which stretches highlights across the rougher direction.
+    pub fn anisotropic<U: Into<f64>, V: Into<f64>>(
+        mut self,
+        roughness_u: U,
+        roughness_v: V,
+    ) -> Self {
+        self.distribution = Ggx::from_roughness(
+            roughness_u.into().clamp(0.0, 1.0),
+            roughness_v.into().clamp(0.0, 1.0),
+        );
+        self
+    }
+
+    /// Turn the directions of the roughness `degrees` counter-clockwise from `u` and `v`.
+    pub fn rotation<T: Into<f64>>(mut self, degrees: T) -> Self {
+        self.rotation = degrees.into().to_radians();
+        self
+    }
+
+    /// The surface's local space, with the roughness along x and y.
+    fn frame(&self, hit: &HitData) -> Frame {
+        hit.frame().rotate(self.rotation)
+    }
+
+    pub fn gold<T: Into<f64>>(roughness: T) -> Self {
+        Conductor::new(
+            color!(0.143, 0.374, 1.442),
+            color!(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper<T: Into<f64>>(roughness: T) -> Self {
+        Conductor::new(
+            color!(0.200, 0.924, 1.102),
+            color!(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn aluminium<T: Into<f64>>(roughness: T) -> Self {
+        Conductor::new(
+            color!(1.657, 0.880, 0.521),
+            color!(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    pub fn silver<T: Into<f64>>(roughness: T) -> Self {
+        Conductor::new(
+            color!(0.155, 0.117, 0.138),
+            color!(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+
+    pub fn chrome<T: Into<f64>>(roughness: T) -> Self {
+        Conductor::new(
+            color!(4.368, 2.910, 1.654),
+            color!(5.206, 4.231, 3.755),
+            roughness,
+        )
+    }
+
+    /// How much light a facet reflects, hit at an angle (given as its cosine) to it.
+    fn fresnel(&self, cos: f64) -> Color {
+        color!(
+            fresnel_conductor(cos, self.eta.r(), self.k.r()),
+            fresnel_conductor(cos, self.eta.g(), self.k.g()),
+            fresnel_conductor(cos, self.eta.b(), self.k.b())
+        )
+    }
+}
+
+/// The exact Fresnel reflectance of a conductor, averaged over both polarizations.
+///
+/// https://seblagarde.wordpress.com/2013/04/29/memo-on-fresnel-equations/
+fn fresnel_conductor(cos: f64, eta: f64, k: f64) -> f64 {
+    let cos = cos.clamp(0.0, 1.0);
+    let cos_squared = cos * cos;
+    let sin_squared = 1.0 - cos_squared;
+
+    let t0 = eta * eta - k * k - sin_squared;
+    let a_squared_plus_b_squared = (t0 * t0 + 4.0 * eta * eta * k * k).sqrt();
+    let a = ((a_squared_plus_b_squared + t0) / 2.0).max(0.0).sqrt();
+
+    let t1 = a_squared_plus_b_squared + cos_squared;
+    let t2 = 2.0 * cos * a;
+    let r_s = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos_squared * a_squared_plus_b_squared + sin_squared * sin_squared;
+    let t4 = t2 * sin_squared;
+    let r_p = r_s * (t3 - t4) / (t3 + t4);
+
+    (r_s + r_p) / 2.0
+}
+
+impl Material for Conductor {
+    fn scatter(&self, hit: &HitData, ray: Ray) -> Option<Scatter> {
+        let frame = self.frame(hit);
+        let wo = frame.to_local(-ray.direction().unit_vec());
+
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        if self.distribution.is_smooth() {
+            return Some(Scatter {
+                ray: Ray::new(
+                    hit.point(),
+                    frame.to_world(reflect(-wo, Vec3::new(0, 0, 1))),
+                ),
+                weight: self.fresnel(wo.z()),
+                pdf: 1.0,
+                lobe: Lobe::SPECULAR_REFLECTION,
+            });
+        }
+
+        // Pick a facet that can be seen from where the light is coming from, and reflect off it.
+        let mut rng = rand::thread_rng();
+        let h = self
+            .distribution
+            .sample_visible_normal(wo, (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)));
+        let wi = reflect(-wo, h);
+
+        // Reflected into the surface, where other facets would be in the way.
+        if wi.z() <= 0.0 {
+            return None;
+        }
+
+        // Most of the BSDF cancels out with the pdf, leaving the Fresnel term and the part of the light
+        // that isn't blocked on its way out (of what could be seen coming in).
+        Some(Scatter {
+            ray: Ray::new(hit.point(), frame.to_world(wi)),
+            weight: self.fresnel(wo.dot(h))
+                * (self.distribution.g2(wo, wi) / self.distribution.g1(wo)),
+            pdf: self.distribution.visible_normal_pdf(wo, h) / (4.0 * wo.dot(h)),
+            lobe: Lobe::GLOSSY_REFLECTION,
+        })
+    }
+
+    fn eval(&self, hit: &HitData, wo: Vec3, wi: Vec3) -> Color {
+        let frame = self.frame(hit);
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+
+        if self.distribution.is_smooth() || wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Color::BLACK;
+        }
+
+        // The facets reflecting `wo` into `wi` face halfway between them.
+        let h = (wo + wi).unit_vec();
+
+        // D * G * F / (4 cos(wo) cos(wi)), times cos(wi).
+        self.fresnel(wo.dot(h))
+            * (self.distribution.d(h) * self.distribution.g2(wo, wi) / (4.0 * wo.z()))
+    }
+
+    fn pdf(&self, hit: &HitData, wo: Vec3, wi: Vec3) -> f64 {
+        let frame = self.frame(hit);
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+
+        if self.distribution.is_smooth() || wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let h = (wo + wi).unit_vec();
+
+        // Turning the pdf of the facet into one of the reflected direction.
+        self.distribution.visible_normal_pdf(wo, h) / (4.0 * wo.dot(h))
+    }
+
+    fn has_non_specular_lobe(&self) -> bool {
+        !self.distribution.is_smooth()
+    }
+}
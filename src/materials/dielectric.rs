@@ -1,87 +1,330 @@
-use super::Material;
-use crate::structs::{Color, HitData, Ray, Vec3};
+use super::{commons::reflect, microfacet::Ggx, Ior, Lobe, Material, Scatter};
+use crate::{
+    color,
+    structs::{Color, Frame, HitData, Ray, Vec3},
+    vec3,
+};
 use rand::Rng;
 
 /// Structure representing a dielectric surface.
 ///
 /// Dielectric materials are ones that allow light to pass through them (eg. glass, water), suffering some refraction
-/// Index of refraction defines the magnitude of refraction of light.
+/// Index of refraction defines the magnitude of refraction of light, and can be a plain number, or
+/// depend on the wavelength (see `Ior`) for glass that splits light into a rainbow.
+///
+/// By default the surface is perfectly smooth and the inside is perfectly clear, see `roughness()` for
+/// frosted glass (and `anisotropic()` for glass brushed or frosted in one direction), and `absorption()` for tinted glass. Objects using it should be closed (like spheres and
+/// watertight meshes), so rays that enter them also leave them.
 #[derive(Debug)]
 pub struct Dielectric {
-    index_of_refraction: f64,
+    index_of_refraction: Ior,
+    distribution: Ggx,
+    /// How far the direction of the roughness is turned from the surface's tangent, in radians.
+    rotation: f64,
+    /// How much of each channel is absorbed per unit of distance travelled inside.
+    absorption: Color,
 }
 
 impl Dielectric {
-    pub fn new(ir: f64) -> Self {
+    pub fn new<T: Into<Ior>>(ir: T) -> Self {
         Self {
-            index_of_refraction: ir,
+            index_of_refraction: ir.into(),
+            distribution: Ggx::from_roughness(0.0, 0.0),
+            rotation: 0.0,
+            absorption: Color::BLACK,
         }
     }
 
-    /// Refraction of light through the material.
-    ///
-    /// If you want to try understanding the derivation :-
-    /// https://raytracing.github.io/books/RayTracingInOneWeekend.html#dielectrics/snell'slaw
-    /// https://physics.stackexchange.com/questions/435512/snells-law-in-vector-form
-    /// https://graphicscompendium.com/raytracing/10-reflection-refraction
-    fn refract(r_in: Vec3, normal: Vec3, refraction_ratio: f64, cos: f64) -> Vec3 {
-        // The output vector can be decomposed into two vectors, one perpendicular to normal and one parallel to it.
-        // Then we can sum these at the end to return the full vector.
+    /// How rough (0 - 1) the surface is, which blurs both reflections and what's seen through it.
+    pub fn roughness<T: Into<f64>>(mut self, roughness: T) -> Self {
+        let roughness = roughness.into().clamp(0.0, 1.0);
 
-        let r_out_perp = (r_in + normal * cos) * refraction_ratio;
-        let r_out_para = normal * -(1.0 - r_out_perp.length_squared()).abs().sqrt();
+        self.distribution = Ggx::from_roughness(roughness, roughness);
+        self
+    }
+
+    /// Separate roughnesses (0 - 1) along the surface's `u` and `v` directions (see `HitData::frame()`),
+    /// like `Conductor::anisotropic()`.
+    pub fn anisotropic<U: Into<f64>, V: Into<f64>>(
+        mut self,
+        roughness_u: U,
+        roughness_v: V,
+    ) -> Self {
+        self.distribution = Ggx::from_roughness(
+            roughness_u.into().clamp(0.0, 1.0),
+            roughness_v.into().clamp(0.0, 1.0),
+        );
+        self
+    }
 
-        r_out_para + r_out_perp
+    /// Turn the directions of the roughness `degrees` counter-clockwise from `u` and `v`.
+    pub fn rotation<T: Into<f64>>(mut self, degrees: T) -> Self {
+        self.rotation = degrees.into().to_radians();
+        self
     }
 
-    /// Reflection, just like in metals (see `metal.rs`);
-    fn reflect(r_in_unit: Vec3, normal: Vec3) -> Vec3 {
-        r_in_unit - normal * (r_in_unit.dot(normal) * 2.0)
+    /// The surface's local space, with the roughness along x and y.
+    fn frame(&self, hit: &HitData) -> Frame {
+        hit.frame().rotate(self.rotation)
     }
 
-    /// Schlick's approximation.
+    /// Tint the inside of the object, so `color` is what's left of white light after going through
+    /// 1 unit of it, and `density` scales how quickly that happens.
     ///
-    /// This outputs a reflection coefficient, which tells how much of light should reflect back,
-    /// depending on the angle (specifically, cos of that angle) and the refraction ratio.
+    /// Thicker parts look darker and more saturated (Beer-Lambert law), like the edges of a green glass bottle.
     ///
-    /// Think how glass becomes opaque when viewed from almost a vertical angle.
-    fn reflectance(cos: f64, refraction_ratio: f64) -> f64 {
-        let r0 = (1.0 - refraction_ratio) / (1.0 + refraction_ratio);
-        let r1 = r0 * r0;
+    /// The tint applies wherever the ray is inside the object, including on its way to other objects
+    /// and lights in there. Objects inside it should be entirely inside (not cutting through its surface),
+    /// as rays are only known to have left an object when they go out through its back face.
+    pub fn absorption<T: Into<f64>>(mut self, color: Color, density: T) -> Self {
+        let density = density.into();
+        let coefficient = |channel: f64| -channel.clamp(1e-6, 1.0).ln() * density;
 
-        r1 + (1.0 - r1) * (1.0 - cos).powi(5)
+        self.absorption = color!(
+            coefficient(color.r()),
+            coefficient(color.g()),
+            coefficient(color.b())
+        );
+        self
     }
-}
 
-impl Material for Dielectric {
-    fn scatter(&self, hit: HitData, r_in: Ray) -> (Ray, Color) {
+    /// The ratio of the index of refraction on the other side of the surface to the side the ray comes from.
+    fn eta(&self, hit: &HitData) -> f64 {
+        let index_of_refraction = self.index_of_refraction.at(hit.wavelength());
+
         // If the ray is hitting the front face, it is entering the object.
         // If its hitting the back, its leaving, thus the index of refraction should be reversed.
-        let refraction_ratio = match hit.is_front_face() {
-            true => 1.0 / self.index_of_refraction,
-            false => self.index_of_refraction,
-        };
+        match hit.is_front_face() {
+            true => index_of_refraction,
+            false => 1.0 / index_of_refraction,
+        }
+    }
+
+    /// The BSDF times the cosine of `wi`, for a rough surface, in its local space (where `wo` is above the surface).
+    fn eval_local(&self, wo: Vec3, wi: Vec3, eta: f64) -> f64 {
+        let distribution = &self.distribution;
+
+        match wi.z() > 0.0 {
+            // Reflection, just like rough metals (see `Conductor`).
+            true => {
+                let h = (wo + wi).unit_vec();
+
+                reflectance(wo.dot(h), eta) * distribution.d(h) * distribution.g2(wo, wi)
+                    / (4.0 * wo.z())
+            }
+            false => {
+                let Some(h) = refraction_half_vector(wo, wi, eta) else {
+                    return 0.0;
+                };
+
+                let denominator = wi.dot(h) + wo.dot(h) / eta;
+
+                (1.0 - reflectance(wo.dot(h), eta))
+                    * distribution.d(h)
+                    * distribution.g2(wo, wi)
+                    * (wi.dot(h) * wo.dot(h)).abs()
+                    / (denominator * denominator * wo.z())
+            }
+        }
+    }
+
+    /// The pdf of `scatter()` picking `wi`, for a rough surface, in its local space.
+    fn pdf_local(&self, wo: Vec3, wi: Vec3, eta: f64) -> f64 {
+        match wi.z() > 0.0 {
+            true => {
+                let h = (wo + wi).unit_vec();
+
+                // The chance of reflecting, times the pdf of the facet turned into one of the reflected direction.
+                reflectance(wo.dot(h), eta) * self.distribution.visible_normal_pdf(wo, h)
+                    / (4.0 * wo.dot(h))
+            }
+            false => {
+                let Some(h) = refraction_half_vector(wo, wi, eta) else {
+                    return 0.0;
+                };
+
+                let denominator = wi.dot(h) + wo.dot(h) / eta;
+
+                // Same, for refracting.
+                (1.0 - reflectance(wo.dot(h), eta))
+                    * self.distribution.visible_normal_pdf(wo, h)
+                    * wi.dot(h).abs()
+                    / (denominator * denominator)
+            }
+        }
+    }
+}
+
+/// Refraction of light through the material, coming from `wo` (pointing away from the surface) through a surface
+/// facing `normal`, into a side with `eta` times the index of refraction.
+///
+/// `None` if the light can't get through at all (total internal reflection).
+///
+/// If you want to try understanding the derivation :-
+/// https://raytracing.github.io/books/RayTracingInOneWeekend.html#dielectrics/snell'slaw
+/// https://physics.stackexchange.com/questions/435512/snells-law-in-vector-form
+/// https://graphicscompendium.com/raytracing/10-reflection-refraction
+fn refract(wo: Vec3, normal: Vec3, eta: f64) -> Option<Vec3> {
+    let cos_in = wo.dot(normal);
+
+    // From snell's law, sin of incidence angle / eta = sin of refracted angle.
+    // However, max. value of sine function is 1, so if this expression exceeds 1, there is no
+    // solution for snell's equation, thus refraction is not possible.
+    let sin_squared_out = (1.0 - cos_in * cos_in).max(0.0) / (eta * eta);
+    if sin_squared_out >= 1.0 {
+        return None;
+    }
+
+    // The output vector can be decomposed into two vectors, one perpendicular to normal and one parallel to it.
+    // Then we can sum these at the end to return the full vector.
+    let cos_out = (1.0 - sin_squared_out).sqrt();
+
+    Some(-wo / eta + normal * (cos_in / eta - cos_out))
+}
+
+/// The exact Fresnel equations for a dielectric, averaged over both polarizations.
+///
+/// This outputs a reflection coefficient, which tells how much of light should reflect back,
+/// depending on the angle (specifically, cos of that angle) and `eta` (see `refract()`).
+///
+/// Think how glass becomes opaque when viewed from almost a vertical angle.
+fn reflectance(cos: f64, eta: f64) -> f64 {
+    let cos_in = cos.clamp(0.0, 1.0);
+    let sin_squared_out = (1.0 - cos_in * cos_in) / (eta * eta);
+
+    // Total internal reflection.
+    if sin_squared_out >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_out = (1.0 - sin_squared_out).sqrt();
+
+    let parallel = (eta * cos_in - cos_out) / (eta * cos_in + cos_out);
+    let perpendicular = (cos_in - eta * cos_out) / (cos_in + eta * cos_out);
+
+    (parallel * parallel + perpendicular * perpendicular) / 2.0
+}
+
+/// The normal of the facet that refracts `wo` into `wi` (in local space, facing up),
+/// or `None` if no facet can (as they'd be on the same side of it).
+fn refraction_half_vector(wo: Vec3, wi: Vec3, eta: f64) -> Option<Vec3> {
+    let h = (wo + wi * eta).unit_vec();
+    let h = match h.z() < 0.0 {
+        true => -h,
+        false => h,
+    };
+
+    match wo.dot(h) > 0.0 && wi.dot(h) < 0.0 {
+        true => Some(h),
+        false => None,
+    }
+}
+
+impl Material for Dielectric {
+    fn scatter(&self, hit: &HitData, r_in: Ray) -> Option<Scatter> {
+        let frame = self.frame(hit);
+        let wo = frame.to_local(-r_in.direction().unit_vec());
+        let eta = self.eta(hit);
+
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let is_smooth = self.distribution.is_smooth();
 
-        let r_in_unit = r_in.direction().unit_vec();
+        // The facet the light hits, which is the surface itself if it's smooth.
+        let h = match is_smooth {
+            true => vec3!(0, 0, 1),
+            false => self
+                .distribution
+                .sample_visible_normal(wo, (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0))),
+        };
 
-        // a ⋅ b = cos θ   (unit vectors)
-        // where θ is the angle of incidence.
-        let cos = r_in_unit.dot(-hit.normal()).min(1.0);
-        let sin = (1.0 - cos * cos).sqrt();
+        let reflection_coefficient = reflectance(wo.dot(h), eta); // Check function comments.
+        let random_double = rng.gen_range(0.0..1.0);
 
-        // From snell's law, refraction ratio * sin of incidence angle = sin of refracted angle.
-        // However, max. value of sine function is 1, so if this expression exceeds 1, there is no
-        // solution for snell's equation, thus refraction is not possible.
-        let cannot_refract = refraction_ratio * sin > 1.0;
+        // Reflect or refract at random, by how much of the light does either, so nothing is lost overall.
+        let (wi, lobe) = match reflection_coefficient > random_double {
+            true => (
+                reflect(-wo, h),
+                match is_smooth {
+                    true => Lobe::SPECULAR_REFLECTION,
+                    false => Lobe::GLOSSY_REFLECTION,
+                },
+            ),
+            false => (
+                // This can't fail, as light that can't refract is always reflected.
+                refract(wo, h, eta)?,
+                match is_smooth {
+                    true => Lobe::SPECULAR_TRANSMISSION,
+                    false => Lobe::GLOSSY_TRANSMISSION,
+                },
+            ),
+        };
 
-        let reflection_coefficient = Self::reflectance(cos, refraction_ratio); // Check method comments.
-        let random_double = rand::thread_rng().gen_range(0.0..1.0);
+        // A rough facet can send the light to the wrong side of the surface, where other facets would be in the way.
+        if (wi.z() > 0.0) == lobe.transmission {
+            return None;
+        }
 
-        let direction = match cannot_refract || (reflection_coefficient > random_double) {
-            true => Self::reflect(r_in_unit, hit.normal()),
-            false => Self::refract(r_in_unit, hit.normal(), refraction_ratio, cos),
+        // For smooth surfaces, the only thing that's random is whether it reflects or refracts.
+        // For rough ones, most of the BSDF cancels out with the pdf (as with `Conductor`).
+        let (weight, pdf) = match is_smooth {
+            true => (
+                Color::WHITE,
+                match lobe.transmission {
+                    true => 1.0 - reflection_coefficient,
+                    false => reflection_coefficient,
+                },
+            ),
+            false => (
+                Color::WHITE * (self.distribution.g2(wo, wi) / self.distribution.g1(wo)),
+                self.pdf_local(wo, wi, eta),
+            ),
         };
 
-        (Ray::new(hit.point(), direction), Color::WHITE)
+        Some(Scatter {
+            ray: Ray::new(hit.point(), frame.to_world(wi)),
+            weight,
+            pdf,
+            lobe,
+        })
+    }
+
+    fn eval(&self, hit: &HitData, wo: Vec3, wi: Vec3) -> Color {
+        let frame = self.frame(hit);
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+
+        match self.distribution.is_smooth() || wo.z() <= 0.0 {
+            true => Color::BLACK,
+            false => Color::WHITE * self.eval_local(wo, wi, self.eta(hit)),
+        }
+    }
+
+    fn pdf(&self, hit: &HitData, wo: Vec3, wi: Vec3) -> f64 {
+        let frame = self.frame(hit);
+        let (wo, wi) = (frame.to_local(wo), frame.to_local(wi));
+
+        match self.distribution.is_smooth() || wo.z() <= 0.0 {
+            true => 0.0,
+            false => self.pdf_local(wo, wi, self.eta(hit)),
+        }
+    }
+
+    fn has_non_specular_lobe(&self) -> bool {
+        !self.distribution.is_smooth()
+    }
+
+    fn is_dispersive(&self) -> bool {
+        self.index_of_refraction.is_dispersive()
+    }
+
+    fn transmittance(&self, distance: f64) -> Color {
+        color!(
+            (-self.absorption.r() * distance).exp(),
+            (-self.absorption.g() * distance).exp(),
+            (-self.absorption.b() * distance).exp()
+        )
     }
 }
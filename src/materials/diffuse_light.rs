@@ -0,0 +1,66 @@
+use super::{Material, Scatter};
+use crate::{
+    structs::{HitData, Ray, Spectrum},
+    textures::Texture,
+};
+use std::sync::Arc;
+
+/// Structure representing a surface that emits light (eg. a lamp, a neon sign).
+///
+/// `emit` is the color of the light given off at each point, multiplied by `intensity`, which
+/// (unlike albedos) can go past 1.0 for lights brighter than white. The surface itself doesn't reflect anything.
+#[derive(Debug)]
+pub struct DiffuseLight {
+    emit: Emit,
+    intensity: f64,
+}
+
+/// The light given off, either the same all over the surface, or varying over it.
+#[derive(Debug)]
+enum Emit {
+    Spectrum(Spectrum),
+    Texture(Arc<dyn Texture + Sync + Send>),
+}
+
+impl DiffuseLight {
+    /// A light of the passed color (or spectrum, like `Spectrum::blackbody()`), multiplied by `intensity`.
+    pub fn new<C: Into<Spectrum>, T: Into<f64>>(color: C, intensity: T) -> Self {
+        DiffuseLight {
+            emit: Emit::Spectrum(color.into()),
+            intensity: intensity.into(),
+        }
+    }
+
+    /// A light whose color varies over the surface (eg. a screen), multiplied by `intensity`.
+    pub fn textured<T: Into<f64>>(emit: Arc<dyn Texture + Sync + Send>, intensity: T) -> Self {
+        DiffuseLight {
+            emit: Emit::Texture(emit),
+            intensity: intensity.into(),
+        }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _: &HitData, _: Ray) -> Option<Scatter> {
+        // Nothing is reflected, so the path ends here.
+        None
+    }
+
+    fn has_non_specular_lobe(&self) -> bool {
+        false
+    }
+
+    fn emitted(&self, hit: &HitData) -> Spectrum {
+        // Only the outward side glows, so a lamp facing down doesn't light up the ceiling.
+        if !hit.is_front_face() {
+            return Spectrum::BLACK;
+        }
+
+        match &self.emit {
+            Emit::Spectrum(spectrum) => spectrum.clone() * self.intensity,
+            Emit::Texture(texture) => {
+                Spectrum::from(texture.value(hit.uv(), hit.point())) * self.intensity
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Indices of refraction that depend on the wavelength of the light, for glass that splits it into a rainbow.
+//!
+//! An `Ior` is either a constant, or one of two models of how the index falls off towards longer wavelengths:
+//! Cauchy's equation (simple, and good enough for water and most glasses) or the Sellmeier equation (how glass
+//! catalogs list their glasses). Presets like `Ior::BK7` and `Ior::DIAMOND` cover common materials.
+//!
+//! https://en.wikipedia.org/wiki/Cauchy%27s_equation
+//! https://en.wikipedia.org/wiki/Sellmeier_equation
+
+/// The wavelength (in nanometers) that indices of refraction are usually quoted at,
+/// the yellow sodium D line, used when a ray isn't following a particular wavelength.
+const REFERENCE_WAVELENGTH: f64 = 587.6;
+
+/// The index of refraction of a transparent material, which can depend on the wavelength of the light.
+///
+/// When it does, glass bends each color by a different amount, splitting white light into a rainbow
+/// (dispersion), like prisms and the sparkle of diamonds.
+/// Wavelengths in the formulas below are in micrometers, as coefficients are usually listed in them.
+#[derive(Debug, Clone, Copy)]
+pub enum Ior {
+    /// The same index for every wavelength.
+    Constant(f64),
+    /// Cauchy's equation, `a + b / λ²`, which is simple and good enough for most glasses in visible light.
+    Cauchy { a: f64, b: f64 },
+    /// The Sellmeier equation, `n² = 1 + Σ b λ² / (λ² - c)`, which is how glass makers list their glasses.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Ior {
+    /// Schott N-BK7, the most common optical glass (lenses, prisms).
+    pub const BK7: Ior = Ior::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+    /// Schott N-SF11, a dense flint glass that disperses light several times more than BK7.
+    pub const DENSE_FLINT: Ior = Ior::Sellmeier {
+        b: [1.73759695, 0.313747346, 1.89878101],
+        c: [0.013188707, 0.0623068142, 155.23629],
+    };
+    /// Fused silica (pure quartz glass), which barely disperses light.
+    pub const FUSED_SILICA: Ior = Ior::Sellmeier {
+        b: [0.6961663, 0.4079426, 0.8974794],
+        c: [0.004679148, 0.013512063, 97.934003],
+    };
+    pub const DIAMOND: Ior = Ior::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030625, 0.011236, 0.0],
+    };
+    pub const WATER: Ior = Ior::Cauchy {
+        a: 1.324,
+        b: 0.0031,
+    };
+
+    /// The index of refraction for light of a wavelength (in nanometers),
+    /// or the one usually quoted for the material if there isn't one.
+    pub fn at(&self, wavelength: Option<f64>) -> f64 {
+        let wavelength = wavelength.unwrap_or(REFERENCE_WAVELENGTH) / 1000.0;
+        let wavelength_squared = wavelength * wavelength;
+
+        match self {
+            Ior::Constant(ior) => *ior,
+            Ior::Cauchy { a, b } => a + b / wavelength_squared,
+            Ior::Sellmeier { b, c } => b
+                .iter()
+                .zip(c)
+                .map(|(b, c)| b * wavelength_squared / (wavelength_squared - c))
+                .fold(1.0, |sum, term| sum + term)
+                .sqrt(),
+        }
+    }
+
+    /// Whether the index of refraction depends on the wavelength.
+    pub fn is_dispersive(&self) -> bool {
+        match self {
+            Ior::Constant(_) => false,
+            Ior::Cauchy { b, .. } => *b != 0.0,
+            Ior::Sellmeier { .. } => true,
+        }
+    }
+}
+
+impl From<f64> for Ior {
+    fn from(ior: f64) -> Self {
+        Ior::Constant(ior)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_match_catalog_values() {
+        // Indices at the d line, as listed by the manufacturers.
+        assert!((Ior::BK7.at(Some(587.6)) - 1.5168).abs() < 1e-4);
+        assert!((Ior::DENSE_FLINT.at(Some(587.6)) - 1.7847).abs() < 1e-4);
+        assert!((Ior::FUSED_SILICA.at(Some(587.6)) - 1.4585).abs() < 1e-4);
+        assert!((Ior::DIAMOND.at(Some(587.6)) - 2.4175).abs() < 1e-3);
+        assert!((Ior::WATER.at(Some(587.6)) - 1.333).abs() < 1e-3);
+    }
+
+    #[test]
+    fn shorter_wavelengths_bend_more() {
+        for ior in [Ior::BK7, Ior::DIAMOND, Ior::WATER] {
+            assert!(ior.is_dispersive());
+            assert!(ior.at(Some(450.0)) > ior.at(Some(650.0)));
+        }
+    }
+
+    #[test]
+    fn constant_ignores_wavelength() {
+        let ior = Ior::from(1.5);
+
+        assert!(!ior.is_dispersive());
+        assert_eq!(ior.at(Some(400.0)), 1.5);
+        assert_eq!(ior.at(None), 1.5);
+    }
+}
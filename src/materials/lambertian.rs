@@ -1,26 +1,56 @@
-use super::{commons::random_unit_vector, Material};
-use crate::structs::{Color, HitData, Ray};
+use super::{Lobe, Material, Scatter};
+use crate::{
+    structs::{Color, HitData, Ray, Vec3},
+    textures::{SolidColor, Texture},
+};
+use std::{f64::consts::PI, sync::Arc};
 
 /// Structure representing a lambertian surface, which is a type of an ideal "matte" surface.
 ///
-/// `albedo` is the effective color of the surface. Everytime a ray bounces off the material,
-/// its respective components are multiplied by a factor of `albedo / 255`.
+/// `albedo` is the effective color of the surface at each point. Everytime a ray bounces off the material,
+/// its respective components are multiplied by `albedo`.
 #[derive(Debug)]
 pub struct Lambertian {
-    albedo: Color,
+    albedo: Arc<dyn Texture + Sync + Send>,
 }
 
 impl Lambertian {
+    /// A surface of a single color.
     pub fn new(albedo: Color) -> Self {
+        Lambertian::textured(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture + Sync + Send>) -> Self {
         Lambertian { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, hit: HitData, _: Ray) -> (Ray, Color) {
+    fn scatter(&self, hit: &HitData, ray: Ray) -> Option<Scatter> {
         // A lambertian scattering simply involves adding a random unit vector to the normal.
-        let direction = hit.normal() + random_unit_vector(hit.normal());
+        // This picks directions in proportion to their cosine with the normal, just like a lambertian surface scatters light.
+        let mut direction = hit.normal() + Vec3::random_unit();
+
+        // If the random vector is almost opposite to the normal, they cancel out, so just go along the normal.
+        if direction.near_zero() {
+            direction = hit.normal();
+        }
+
+        // As the directions are picked just like the light scatters, only the albedo is left over.
+        Some(Scatter {
+            ray: Ray::new(hit.point(), direction),
+            weight: self.albedo.value(hit.uv(), hit.point()),
+            pdf: self.pdf(hit, -ray.direction().unit_vec(), direction.unit_vec()),
+            lobe: Lobe::DIFFUSE_REFLECTION,
+        })
+    }
+
+    fn eval(&self, hit: &HitData, _: Vec3, wi: Vec3) -> Color {
+        // Light is scattered equally in every direction, and dividing the albedo by π keeps the total in check.
+        self.albedo.value(hit.uv(), hit.point()) * (wi.dot(hit.normal()).max(0.0) / PI)
+    }
 
-        (Ray::new(hit.point(), direction), self.albedo)
+    fn pdf(&self, hit: &HitData, _: Vec3, wi: Vec3) -> f64 {
+        wi.dot(hit.normal()).max(0.0) / PI
     }
 }
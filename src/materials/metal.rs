@@ -1,40 +1,111 @@
-use super::{commons::random_unit_vector, Material};
-use crate::structs::{Color, HitData, Ray};
+use super::{commons::reflect, Lobe, Material, Scatter};
+use crate::{
+    structs::{Color, HitData, Ray, Vec3},
+    textures::{SolidColor, Texture},
+};
+use std::{f64::consts::PI, sync::Arc};
 
 /// Structure representing a metal surface.
 ///
-/// `albedo` is the effective color of the surface. Everytime a ray bounces off the material,
-/// its respective components are multiplied by a factor of `albedo / 255`.
+/// `albedo` is the effective color of the surface at each point. Everytime a ray bounces off the material,
+/// its respective components are multiplied by `albedo`.
 ///
 /// `fuzz` is the factor for the randomness induced in the reflected ray's direction.
 /// It should be between 0 and 1. Values above 1 just result in noise, and negatives are
 /// the same as the randomness covers negative and positive deviations equally.
+/// Rays fuzzed below the surface are absorbed.
+///
+/// For realistic (rough) metals, see `Conductor`.
 #[derive(Debug)]
 pub struct Metal {
-    albedo: Color,
+    albedo: Arc<dyn Texture + Sync + Send>,
     fuzz: f64,
 }
 
 impl Metal {
+    /// A metal of a single color.
     pub fn new<T: Into<f64>>(albedo: Color, fuzz: T) -> Self {
+        Metal::textured(Arc::new(SolidColor::new(albedo)), fuzz)
+    }
+
+    pub fn textured<T: Into<f64>>(albedo: Arc<dyn Texture + Sync + Send>, fuzz: T) -> Self {
         Metal {
             albedo,
-            fuzz: fuzz.into(),
+            fuzz: fuzz.into().abs(),
+        }
+    }
+
+    /// The pdf of the fuzzed reflection picking `wi`, when the perfect reflection is `reflected` (both unit vectors).
+    ///
+    /// The fuzzed direction points at a random point on a sphere of radius `fuzz` around the tip of the reflection,
+    /// so it's the pdf of that point (one over the sphere's area), times how much of the sphere's surface
+    /// is seen per solid angle where `wi` crosses it (for both crossings, if both are in front).
+    fn fuzz_pdf(&self, reflected: Vec3, wi: Vec3) -> f64 {
+        // Distances along `wi` to where it crosses the sphere, solving |t * wi - reflected| = fuzz.
+        let cos = wi.dot(reflected);
+        let discriminant = cos * cos - 1.0 + self.fuzz * self.fuzz;
+
+        if discriminant <= 0.0 {
+            return 0.0;
         }
+
+        let root = discriminant.sqrt();
+
+        // The surface of the sphere is at an angle to `wi`, with a cosine of `root / fuzz`.
+        [cos - root, cos + root]
+            .into_iter()
+            .filter(|&t| t > 0.0)
+            .map(|t| t * t / (4.0 * PI * self.fuzz * root))
+            .sum()
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, hit: HitData, ray: Ray) -> (Ray, Color) {
-        let v = ray.direction().unit_vec();
+    fn scatter(&self, hit: &HitData, ray: Ray) -> Option<Scatter> {
+        let reflected = reflect(ray.direction().unit_vec(), hit.normal());
+        let albedo = self.albedo.value(hit.uv(), hit.point());
+
+        // A perfect mirror.
+        if self.fuzz == 0.0 {
+            return Some(Scatter {
+                ray: Ray::new(hit.point(), reflected),
+                weight: albedo,
+                pdf: 1.0,
+                lobe: Lobe::SPECULAR_REFLECTION,
+            });
+        }
+
+        // The `random_unit()` introduces some randomness into the scattered ray's direction, creating "fuzz".
+        let direction = reflected + Vec3::random_unit() * self.fuzz;
+
+        // Fuzzed into (or along) the surface.
+        if direction.near_zero() || direction.dot(hit.normal()) <= 0.0 {
+            return None;
+        }
+
+        // The fuzz isn't derived from any physical model, so the light scattered is simply defined to be the
+        // albedo times the pdf of picking the direction (see `eval()`), which leaves the albedo as the weight.
+        Some(Scatter {
+            ray: Ray::new(hit.point(), direction),
+            weight: albedo,
+            pdf: self.fuzz_pdf(reflected, direction.unit_vec()),
+            lobe: Lobe::GLOSSY_REFLECTION,
+        })
+    }
+
+    fn eval(&self, hit: &HitData, wo: Vec3, wi: Vec3) -> Color {
+        // Defined as the albedo times the pdf, so the fuzz spreads the reflection out exactly as it's sampled.
+        self.albedo.value(hit.uv(), hit.point()) * self.pdf(hit, wo, wi)
+    }
 
-        // Resulting vector = v - 2b
-        // Where `b` has direction along normal and magnitude of v's "height" along normal.
-        //
-        // The `random_unit_vector()` introduces some randomness into the scattered ray's direction, creating "fuzz".
-        let direction = v - (hit.normal() * (v.dot(hit.normal()) * 2.0))
-            + random_unit_vector(hit.normal()) * self.fuzz;
+    fn pdf(&self, hit: &HitData, wo: Vec3, wi: Vec3) -> f64 {
+        match self.fuzz > 0.0 && wi.dot(hit.normal()) > 0.0 {
+            true => self.fuzz_pdf(reflect(-wo, hit.normal()), wi),
+            false => 0.0,
+        }
+    }
 
-        (Ray::new(hit.point(), direction), self.albedo)
+    fn has_non_specular_lobe(&self) -> bool {
+        self.fuzz > 0.0
     }
 }
@@ -0,0 +1,115 @@
+//! The GGX (or Trowbridge-Reitz) microfacet distribution, for rough surfaces.
+//!
+//! Rough surfaces are treated as lots of tiny perfectly smooth facets, facing all over the place (the rougher,
+//! the more spread out), which shadow and hide each other (Smith's model). Everything here is in the surface's
+//! local space (see `Frame`), where the normal is +z.
+//!
+//! https://jcgt.org/published/0003/02/03/paper.pdf
+//! https://jcgt.org/published/0007/04/01/paper.pdf (sampling)
+
+use crate::{structs::Vec3, vec3};
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ggx {
+    /// How spread out the facets are along x and y.
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl Ggx {
+    /// Anything smoother than this is treated as perfectly smooth (see `is_smooth()`),
+    /// as the distribution gets too sharp to work with.
+    const MIN_ALPHA: f64 = 1e-3;
+
+    /// Alphas are kept above zero, so a surface that's only smooth in one direction still works.
+    pub fn new(alpha_x: f64, alpha_y: f64) -> Self {
+        Ggx {
+            alpha_x: alpha_x.max(1e-4),
+            alpha_y: alpha_y.max(1e-4),
+        }
+    }
+
+    /// A distribution from perceptual roughnesses (0 - 1) along x and y, which are squared as that looks more even.
+    pub fn from_roughness(roughness_x: f64, roughness_y: f64) -> Self {
+        Ggx::new(roughness_x * roughness_x, roughness_y * roughness_y)
+    }
+
+    /// Is the surface too smooth to be anything but a perfect mirror ?
+    pub fn is_smooth(&self) -> bool {
+        self.alpha_x.max(self.alpha_y) < Self::MIN_ALPHA
+    }
+
+    /// The density of facets facing `h`.
+    pub fn d(&self, h: Vec3) -> f64 {
+        if h.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let (x, y) = (h.x() / self.alpha_x, h.y() / self.alpha_y);
+        let denominator = x * x + y * y + h.z() * h.z();
+
+        1.0 / (PI * self.alpha_x * self.alpha_y * denominator * denominator)
+    }
+
+    /// Smith's Λ, for how much of the surface is hidden looking at it from `w`.
+    fn lambda(&self, w: Vec3) -> f64 {
+        let (x, y) = (w.x() * self.alpha_x, w.y() * self.alpha_y);
+        let tan_squared = (x * x + y * y) / (w.z() * w.z());
+
+        ((1.0 + tan_squared).sqrt() - 1.0) / 2.0
+    }
+
+    /// How much of the surface can be seen from `w`.
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// How much of the surface can be seen from both `wo` and `wi`.
+    pub fn g2(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Pick a facet normal among the ones that can be seen from `wo`, in proportion to how much of each is seen,
+    /// from two uniformly random numbers (0 - 1).
+    ///
+    /// This wastes way less samples than picking from all the normals, as facets facing away are never picked.
+    pub fn sample_visible_normal(&self, wo: Vec3, (r0, r1): (f64, f64)) -> Vec3 {
+        // Stretch the view direction, so the distribution turns into a hemisphere of radius 1.
+        let view = vec3!(self.alpha_x * wo.x(), self.alpha_y * wo.y(), wo.z()).unit_vec();
+
+        // An orthonormal basis around it.
+        let length_squared = view.x() * view.x() + view.y() * view.y();
+        let t1 = match length_squared > 0.0 {
+            true => vec3!(-view.y(), view.x(), 0) / length_squared.sqrt(),
+            false => vec3!(1, 0, 0),
+        };
+        let t2 = view.cross(t1);
+
+        // A point on the disc the hemisphere covers seen from the view direction,
+        // with the half of it that's partly hidden squashed.
+        let radius = r0.sqrt();
+        let angle = 2.0 * PI * r1;
+        let p1 = radius * angle.cos();
+        let s = (1.0 + view.z()) / 2.0;
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * radius * angle.sin();
+
+        // Up onto the hemisphere, and unstretched.
+        let normal = t1 * p1 + t2 * p2 + view * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+
+        vec3!(
+            self.alpha_x * normal.x(),
+            self.alpha_y * normal.y(),
+            normal.z().max(1e-6)
+        )
+        .unit_vec()
+    }
+
+    /// The pdf of `sample_visible_normal()` picking `h`.
+    pub fn visible_normal_pdf(&self, wo: Vec3, h: Vec3) -> f64 {
+        match wo.z() > 0.0 {
+            true => self.g1(wo) * wo.dot(h).max(0.0) * self.d(h) / wo.z(),
+            false => 0.0,
+        }
+    }
+}
@@ -1,14 +1,134 @@
-use crate::structs::{Color, HitData, Ray};
+use crate::structs::{Color, HitData, Ray, Spectrum, Vec3};
+
+/// How spread out the directions a lobe scatters light in are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LobeKind {
+    /// All over the hemisphere, like matte surfaces.
+    Diffuse,
+    /// Around one main direction, like brushed or rough metals.
+    Glossy,
+    /// In just one direction, like mirrors and glass.
+    Specular,
+}
+
+/// The part of a material's scattering that a `Scatter` came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lobe {
+    pub kind: LobeKind,
+    /// Does the light go through the surface (instead of bouncing off it) ?
+    pub transmission: bool,
+}
+
+impl Lobe {
+    pub const DIFFUSE_REFLECTION: Lobe = Lobe {
+        kind: LobeKind::Diffuse,
+        transmission: false,
+    };
+
+    pub const GLOSSY_REFLECTION: Lobe = Lobe {
+        kind: LobeKind::Glossy,
+        transmission: false,
+    };
+
+    pub const SPECULAR_REFLECTION: Lobe = Lobe {
+        kind: LobeKind::Specular,
+        transmission: false,
+    };
+
+    pub const GLOSSY_TRANSMISSION: Lobe = Lobe {
+        kind: LobeKind::Glossy,
+        transmission: true,
+    };
+
+    pub const SPECULAR_TRANSMISSION: Lobe = Lobe {
+        kind: LobeKind::Specular,
+        transmission: true,
+    };
+
+    /// Specular lobes can't be evaluated for any other direction than the one they picked,
+    /// so the lights can't be sampled directly from them.
+    pub fn is_specular(&self) -> bool {
+        self.kind == LobeKind::Specular
+    }
+}
+
+/// Light scattering off a surface, as picked by `Material::scatter()`.
+#[derive(Debug, Clone, Copy)]
+pub struct Scatter {
+    /// The ray leaving the surface, which the light is traced further along.
+    pub ray: Ray,
+    /// What light coming back along `ray` gets multiplied by (the BSDF times the cosine, over the pdf),
+    /// like the `albedo` of most materials.
+    pub weight: Color,
+    /// The pdf (over directions) of picking the ray's direction.
+    /// For specular lobes, which only have the one direction, it's the probability of picking that lobe.
+    pub pdf: f64,
+    pub lobe: Lobe,
+}
 
 pub trait Material: std::fmt::Debug {
-    fn scatter(&self, hit: HitData, ray: Ray) -> (Ray, Color);
+    /// Pick a direction for light hitting the surface along `ray` to scatter off in,
+    /// or `None` if it's absorbed.
+    fn scatter(&self, hit: &HitData, ray: Ray) -> Option<Scatter>;
+
+    /// The light given off by the surface at the hit point.
+    /// Most materials don't glow, so this is black unless overriden.
+    fn emitted(&self, _hit: &HitData) -> Spectrum {
+        Spectrum::BLACK
+    }
+
+    /// How much light coming in from `wi` is scattered out towards `wo` (both unit vectors pointing away
+    /// from the surface), times the cosine of `wi` with the normal.
+    ///
+    /// This is needed to light the surface by sampling the lights directly, which is only possible for
+    /// materials that aren't entirely specular (so black, the default, means it can't be done).
+    fn eval(&self, _hit: &HitData, _wo: Vec3, _wi: Vec3) -> Color {
+        Color::BLACK
+    }
+
+    /// The probability (density) of `scatter()` sending light from `wo` off in `wi`.
+    ///
+    /// Zero (the default) for specular materials, which scatter in only one direction, like mirrors and glass.
+    fn pdf(&self, _hit: &HitData, _wo: Vec3, _wi: Vec3) -> f64 {
+        0.0
+    }
+
+    /// Whether the material scatters light over a range of directions (ie. anything but specular lobes),
+    /// which `eval()` and `pdf()` describe.
+    ///
+    /// If not (like mirrors, smooth glass and lights), sampling the lights at the surface can't find anything,
+    /// so it's skipped.
+    fn has_non_specular_lobe(&self) -> bool {
+        true
+    }
+
+    /// What light is multiplied by after travelling `distance` through the inside of an object made of the material
+    /// (see `Camera::ray_color()` for how the ray keeps track of what it's inside).
+    ///
+    /// Only media that absorb light (like tinted glass) need this, so it's white by default.
+    fn transmittance(&self, _distance: f64) -> Color {
+        Color::WHITE
+    }
+
+    /// Whether the material treats light differently depending on its wavelength (like prisms),
+    /// in which case the hit carries a single wavelength (see `HitData::wavelength()`) to use instead of RGB.
+    fn is_dispersive(&self) -> bool {
+        false
+    }
 }
 
 mod commons;
+mod conductor;
 mod dielectric;
+mod diffuse_light;
+mod ior;
 mod lambertian;
 mod metal;
+mod microfacet;
 
+pub use conductor::Conductor;
 pub use dielectric::Dielectric;
+pub use diffuse_light::DiffuseLight;
+pub use ior::Ior;
 pub use lambertian::Lambertian;
 pub use metal::Metal;
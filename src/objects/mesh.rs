@@ -0,0 +1,166 @@
+//! An indexed triangle mesh geometry for an object.
+
+use super::{
+    triangle::{face_normal, intersect},
+    HitData, Object,
+};
+use crate::{
+    materials::Material,
+    structs::{Aabb, Bvh, Interval, Point3, Ray, Vec3},
+};
+use std::sync::Arc;
+
+/// A mesh of triangles, all sharing one material.
+///
+/// The vertex data lives in separate buffers (behind `Arc`s so multiple meshes can share them),
+/// and every triangle is three indices into them. The same index is used for the position,
+/// normal and UV of a vertex.
+///
+/// The mesh keeps its own `Bvh` over its triangles, so to the scene it's just one object,
+/// no matter how many triangles it has.
+#[derive(Debug)]
+pub struct TriangleMesh {
+    positions: Arc<Vec<Point3>>,
+    /// Per-vertex normals, for smooth shading. Without them, the flat face normal is used.
+    normals: Option<Arc<Vec<Vec3>>>,
+    /// Per-vertex surface coordinates.
+    uvs: Option<Arc<Vec<(f64, f64)>>>,
+    indices: Vec<[usize; 3]>,
+    material: Arc<dyn Material + Sync + Send>,
+    bvh: Bvh,
+}
+
+impl TriangleMesh {
+    /// Construct a mesh from the vertex buffers and triangle indices.
+    ///
+    /// Panics if any index is out of bounds for any of the passed buffers.
+    pub fn new(
+        positions: Arc<Vec<Point3>>,
+        normals: Option<Arc<Vec<Vec3>>>,
+        uvs: Option<Arc<Vec<(f64, f64)>>>,
+        indices: Vec<[usize; 3]>,
+        material: Arc<dyn Material + Sync + Send>,
+    ) -> Self {
+        // The smallest buffer decides which indices are valid.
+        let vertex_count = [
+            Some(positions.len()),
+            normals.as_ref().map(|normals| normals.len()),
+            uvs.as_ref().map(|uvs| uvs.len()),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(0);
+
+        assert!(
+            indices.iter().flatten().all(|&i| i < vertex_count),
+            "Triangle mesh index out of bounds."
+        );
+
+        let bounds: Vec<Aabb> = indices
+            .iter()
+            .map(|&[a, b, c]| {
+                Aabb::new(positions[a], positions[b]).union(Aabb::new(positions[c], positions[c]))
+            })
+            .collect();
+
+        TriangleMesh {
+            bvh: Bvh::new(&bounds),
+            positions,
+            normals,
+            uvs,
+            indices,
+            material,
+        }
+    }
+
+    /// No. of triangles in the mesh.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Check if the ray hits a specific triangle of the mesh.
+    fn does_hit_triangle(&self, index: usize, ray: Ray, interval: Interval) -> Option<HitData> {
+        let [i0, i1, i2] = self.indices[index];
+        let vertices = [self.positions[i0], self.positions[i1], self.positions[i2]];
+
+        let (t, b1, b2) = intersect(ray, interval, vertices)?;
+        let b0 = 1.0 - b1 - b2;
+
+        // Which side is the front is always decided by the actual geometry,
+        // even if the shading normal says otherwise.
+        let outward_normal = face_normal(vertices).unit_vec();
+        let is_front_face = ray.direction().dot(outward_normal) < 0.0;
+
+        // Smooth shading: blend the normals of the vertices by how close the hit point is to each.
+        let shading_normal = match &self.normals {
+            Some(normals) => (normals[i0] * b0 + normals[i1] * b1 + normals[i2] * b2).unit_vec(),
+            None => outward_normal,
+        };
+
+        let normal = match is_front_face {
+            true => shading_normal,
+            false => -shading_normal,
+        };
+
+        let [p0, p1, p2] = vertices;
+        let (edge1, edge2) = (p1 - p0, p2 - p0);
+
+        // The tangent is the way `u` increases along the triangle.
+        let ((u, v), tangent) = match &self.uvs {
+            Some(uvs) => {
+                let (du1, dv1) = (uvs[i1].0 - uvs[i0].0, uvs[i1].1 - uvs[i0].1);
+                let (du2, dv2) = (uvs[i2].0 - uvs[i0].0, uvs[i2].1 - uvs[i0].1);
+                let determinant = du1 * dv2 - du2 * dv1;
+
+                // Solving edge = dP/du * du + dP/dv * dv for both edges,
+                // unless the UVs are squashed into a line, which doesn't say which way `u` goes.
+                let tangent = match determinant.abs() > 1e-12 {
+                    true => (edge1 * dv2 - edge2 * dv1) / determinant,
+                    false => edge1,
+                };
+
+                (
+                    (
+                        uvs[i0].0 * b0 + uvs[i1].0 * b1 + uvs[i2].0 * b2,
+                        uvs[i0].1 * b0 + uvs[i1].1 * b1 + uvs[i2].1 * b2,
+                    ),
+                    tangent,
+                )
+            }
+            None => ((b1, b2), edge1),
+        };
+
+        let hit_data = HitData::new(
+            ray.at(t),
+            t,
+            self.material.clone(),
+            is_front_face,
+            normal,
+            tangent,
+        )
+        .with_uv(u, v);
+
+        Some(hit_data)
+    }
+}
+
+impl Object for TriangleMesh {
+    fn material(&self) -> Arc<dyn Material + Sync + Send> {
+        Arc::clone(&self.material)
+    }
+
+    fn bounding_box(&self, _: Interval) -> Aabb {
+        self.bvh.bounding_box()
+    }
+
+    fn does_hit(&self, ray: Ray, interval: Interval, _: f64) -> Option<HitData> {
+        self.bvh.does_hit(ray, interval, |index, interval| {
+            self.does_hit_triangle(index, ray, interval)
+        })
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     materials::Material,
-    structs::{HitData, Interval, Ray},
+    structs::{Aabb, HitData, Interval, Ray},
 };
 use std::sync::Arc;
 
@@ -9,8 +9,18 @@ use std::sync::Arc;
 pub trait Object: std::fmt::Debug {
     fn does_hit(&self, ray: Ray, interval: Interval, time: f64) -> Option<HitData>;
     fn material(&self) -> Arc<dyn Material + Sync + Send>;
+
+    /// A box containing the object at every point of time within `shutter`.
+    ///
+    /// Moving objects should cover everywhere they go while the shutter is open,
+    /// otherwise rays at some times would miss them.
+    fn bounding_box(&self, shutter: Interval) -> Aabb;
 }
 
+mod mesh;
 mod sphere;
+mod triangle;
 
+pub use mesh::TriangleMesh;
 pub use sphere::Sphere;
+pub use triangle::Triangle;
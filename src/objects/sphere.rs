@@ -3,9 +3,10 @@
 use super::{HitData, Object};
 use crate::{
     materials::Material,
-    structs::{Interval, Point3, Ray, Vec3},
+    structs::{Aabb, Interval, Point3, Ray, Vec3},
+    vec3,
 };
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
 /// The main structure defining a sphere, with a center, radius, and the material.
 #[derive(Debug)]
@@ -32,11 +33,33 @@ impl Sphere {
     }
 }
 
+/// The spherical coordinates of a point on the unit sphere, as UVs.
+///
+/// `u` goes around the y-axis (from -x, through +z, +x and -z), and `v` from the bottom to the top.
+fn spherical_uv(point: Vec3) -> (f64, f64) {
+    let theta = (-point.y()).clamp(-1.0, 1.0).acos();
+    let phi = (-point.z()).atan2(point.x()) + PI;
+
+    (phi / (2.0 * PI), theta / PI)
+}
+
 impl Object for Sphere {
     fn material(&self) -> Arc<dyn Material + Sync + Send> {
         Arc::clone(&self.material)
     }
 
+    /// The union of the boxes around the sphere when the shutter opens and when it closes.
+    /// As the sphere moves in a straight line, it stays within this box the entire time.
+    fn bounding_box(&self, shutter: Interval) -> Aabb {
+        // The radius can be negative (for hollow spheres), the box cannot.
+        let radius = vec3!(self.radius.abs(), self.radius.abs(), self.radius.abs());
+
+        let start = self.center + self.velocity * shutter.min;
+        let end = self.center + self.velocity * shutter.max;
+
+        Aabb::new(start - radius, start + radius).union(Aabb::new(end - radius, end + radius))
+    }
+
     /// Calculating whether a ray hits the sphere.
     fn does_hit(&self, ray: Ray, interval: Interval, time: f64) -> Option<HitData> {
         let center = self.center + self.velocity * time;
@@ -70,13 +93,22 @@ impl Object for Sphere {
             false => -outward_normal,
         };
 
+        // The radius is negative for hollow spheres, so the UVs are found from the actual direction.
+        let direction = (ray.at(root) - center) / self.radius.abs();
+        let (u, v) = spherical_uv(direction);
+
+        // The way `u` goes around the y-axis (which is undefined at the poles, where any tangent works).
+        let tangent = vec3!(direction.z(), 0, -direction.x());
+
         let hit_data = HitData::new(
             ray.at(root),
             root,
             self.material.clone(),
             is_front_face,
             normal,
-        );
+            tangent,
+        )
+        .with_uv(u, v);
 
         Some(hit_data)
     }
@@ -0,0 +1,188 @@
+//! A triangle geometry for an object.
+
+use super::{HitData, Object};
+use crate::{
+    materials::Material,
+    structs::{Aabb, Interval, Point3, Ray, Vec3},
+};
+use std::sync::Arc;
+
+/// The main structure defining a single triangle, with its three vertices and the material.
+///
+/// For many triangles sharing vertices (ie. any actual model), see `TriangleMesh`.
+#[derive(Debug)]
+pub struct Triangle {
+    vertices: [Point3; 3],
+    material: Arc<dyn Material + Sync + Send>,
+}
+
+impl Triangle {
+    pub fn new(a: Point3, b: Point3, c: Point3, material: Arc<dyn Material + Sync + Send>) -> Self {
+        Triangle {
+            vertices: [a, b, c],
+            material,
+        }
+    }
+}
+
+/// Calculating whether a ray hits a triangle, using the Möller–Trumbore algorithm.
+///
+/// Returns the ray's `t` at the hit and the barycentric coordinates of the hit point
+/// (the weights of the second and third vertex, the first one's being `1 - b1 - b2`).
+///
+/// https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
+pub(super) fn intersect(
+    ray: Ray,
+    interval: Interval,
+    [p0, p1, p2]: [Point3; 3],
+) -> Option<(f64, f64, f64)> {
+    let edge1 = p1 - p0;
+    let edge2 = p2 - p0;
+
+    let p = ray.direction().cross(edge2);
+    let determinant = edge1.dot(p);
+
+    // The ray is parallel to the triangle's plane.
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+
+    let inverse = 1.0 / determinant;
+    let distance = ray.origin() - p0;
+
+    let b1 = distance.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let q = distance.cross(edge1);
+
+    let b2 = ray.direction().dot(q) * inverse;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inverse;
+    if interval.excludes(t) {
+        return None;
+    }
+
+    Some((t, b1, b2))
+}
+
+/// The (unnormalized) normal of a triangle, following the right-hand rule for the vertex order.
+pub(super) fn face_normal([p0, p1, p2]: [Point3; 3]) -> Vec3 {
+    (p1 - p0).cross(p2 - p0)
+}
+
+impl Object for Triangle {
+    fn material(&self) -> Arc<dyn Material + Sync + Send> {
+        Arc::clone(&self.material)
+    }
+
+    fn bounding_box(&self, _: Interval) -> Aabb {
+        let [a, b, c] = self.vertices;
+        Aabb::new(a, b).union(Aabb::new(c, c))
+    }
+
+    fn does_hit(&self, ray: Ray, interval: Interval, _: f64) -> Option<HitData> {
+        let (t, b1, b2) = intersect(ray, interval, self.vertices)?;
+
+        let outward_normal = face_normal(self.vertices).unit_vec();
+
+        let is_front_face = ray.direction().dot(outward_normal) < 0.0;
+        let normal = match is_front_face {
+            true => outward_normal,
+            false => -outward_normal,
+        };
+
+        // With no UVs of its own, the barycentric coordinates are the best surface coordinates we have,
+        // so `u` goes along the first edge.
+        let [p0, p1, _] = self.vertices;
+        let hit_data = HitData::new(
+            ray.at(t),
+            t,
+            self.material.clone(),
+            is_front_face,
+            normal,
+            p1 - p0,
+        )
+        .with_uv(b1, b2);
+
+        Some(hit_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{interval, materials::Lambertian, point3, structs::Color, vec3};
+
+    /// A right triangle in the z = 0 plane, facing +z.
+    fn triangle() -> Triangle {
+        Triangle::new(
+            point3!(0, 0, 0),
+            point3!(1, 0, 0),
+            point3!(0, 1, 0),
+            Arc::new(Lambertian::new(Color::WHITE)),
+        )
+    }
+
+    /// Shoot a ray straight down (or up, from below) at a point of the plane.
+    fn hit_at(x: f64, y: f64, from_below: bool) -> Option<HitData> {
+        let (z, direction) = match from_below {
+            true => (-2.0, 1.0),
+            false => (2.0, -1.0),
+        };
+        let ray = Ray::new(point3!(x, y, z), vec3!(0, 0, direction));
+
+        triangle().does_hit(ray, interval!(0.001, f64::INFINITY), 0.0)
+    }
+
+    #[test]
+    fn hits_inside() {
+        let hit = hit_at(0.25, 0.25, false).unwrap();
+
+        assert!((hit.time() - 2.0).abs() < 1e-12);
+        assert!(hit.is_front_face());
+        assert!((hit.normal().z() - 1.0).abs() < 1e-12);
+        assert!(hit.point().z().abs() < 1e-12);
+    }
+
+    #[test]
+    fn misses_outside() {
+        assert!(hit_at(0.6, 0.6, false).is_none());
+        assert!(hit_at(-0.1, 0.5, false).is_none());
+        assert!(hit_at(0.5, -0.1, true).is_none());
+
+        // Along the plane.
+        let ray = Ray::new(point3!(-1, 0.25, 0), vec3!(1, 0, 0));
+        assert!(triangle()
+            .does_hit(ray, interval!(0.001, f64::INFINITY), 0.0)
+            .is_none());
+
+        // Past the end of the interval.
+        let ray = Ray::new(point3!(0.25, 0.25, 2), vec3!(0, 0, -1));
+        assert!(triangle().does_hit(ray, interval!(0.001, 1), 0.0).is_none());
+    }
+
+    #[test]
+    fn back_face_faces_the_ray() {
+        let hit = hit_at(0.25, 0.25, true).unwrap();
+
+        assert!(!hit.is_front_face());
+        assert!((hit.normal().z() + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn uvs_are_barycentric() {
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.2, 0.3), (0.5, 0.5)] {
+            for from_below in [false, true] {
+                let (u, v) = hit_at(x, y, from_below).unwrap().uv();
+
+                // The weights of the second and third vertex, which are at x = 1 and y = 1.
+                assert!((u - x).abs() < 1e-12 && (v - y).abs() < 1e-12);
+            }
+        }
+    }
+}
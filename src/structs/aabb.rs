@@ -0,0 +1,98 @@
+//! A structure representing an axis-aligned bounding box.
+
+use super::{Interval, Point3, Ray};
+
+/// An axis-aligned bounding box, described by its two opposite corners.
+///
+/// These are used to quickly rule out objects that a ray can't possibly hit (see `Bvh`).
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    min: Point3,
+    max: Point3,
+}
+
+impl Aabb {
+    /// A box containing nothing, so that any union with it is just the other box.
+    pub const EMPTY: Aabb = Aabb {
+        min: Point3::INFINITY,
+        max: Point3::NEG_INFINITY,
+    };
+
+    pub fn min(&self) -> Point3 {
+        self.min
+    }
+
+    pub fn max(&self) -> Point3 {
+        self.max
+    }
+
+    /// The smallest box containing both `self` and `other`.
+    pub fn union(&self, other: Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Surface area of the box, used for the surface area heuristic when building a `Bvh`.
+    pub fn surface_area(&self) -> f64 {
+        let d = self.max - self.min;
+
+        if d.x() < 0.0 || d.y() < 0.0 || d.z() < 0.0 {
+            return 0.0;
+        }
+
+        2.0 * (d.x() * d.y() + d.y() * d.z() + d.z() * d.x())
+    }
+
+    /// Check if a ray passes through the box anywhere within the interval.
+    ///
+    /// This is the "slab" method: the box is the intersection of three slabs (one per axis),
+    /// so we clip the interval against each slab in turn and see if anything is left.
+    pub fn does_hit(&self, ray: Ray, interval: Interval) -> bool {
+        let origin = ray.origin();
+        let direction = ray.direction();
+
+        let slabs = [
+            (origin.x(), direction.x(), self.min.x(), self.max.x()),
+            (origin.y(), direction.y(), self.min.y(), self.max.y()),
+            (origin.z(), direction.z(), self.min.z(), self.max.z()),
+        ];
+
+        let mut t_min = interval.min;
+        let mut t_max = interval.max;
+
+        for (origin, direction, min, max) in slabs {
+            let inverse = 1.0 / direction;
+
+            let mut t0 = (min - origin) * inverse;
+            let mut t1 = (max - origin) * inverse;
+
+            if inverse < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            // `max` / `min` (instead of comparisons) so that NaNs (from 0 * infinity) are ignored.
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+
+            if t_max < t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Create a box from any two opposite corners.
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Aabb {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+}
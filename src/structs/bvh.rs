@@ -0,0 +1,381 @@
+//! A bounding volume hierarchy, so that a ray doesn't have to be tested against every object.
+
+use super::{Aabb, HitData, Interval, Point3, Ray};
+
+/// No. of buckets the centroids are sorted into when looking for the best split.
+const BINS: usize = 12;
+/// Nodes with at most this many primitives are allowed to become leaves.
+const MAX_LEAF_SIZE: usize = 4;
+/// Cost of visiting a node, relative to the cost of intersecting a primitive (which is 1).
+const TRAVERSAL_COST: f64 = 0.5;
+/// Max. depth of the tree, which bounds the size of the stack needed while traversing it.
+const MAX_DEPTH: usize = 64;
+
+/// A bounding volume hierarchy over a list of primitives.
+///
+/// The hierarchy only knows about the bounding boxes of the primitives, and refers to them
+/// by their index in the list it was built from. This way the same structure accelerates
+/// both a `Scene` (primitives are objects) and a mesh (primitives are triangles).
+#[derive(Debug)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    /// Primitive indices, reordered so that every leaf covers a contiguous range.
+    indices: Vec<usize>,
+}
+
+/// A node of the tree, stored flat in `Bvh::nodes`.
+///
+/// The first child of an interior node is always the node right after it,
+/// so only the second child needs to be stored.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    bbox: Aabb,
+    /// For leaves, the start of its range in `Bvh::indices`.
+    /// For interior nodes, the index of the second child.
+    offset: usize,
+    /// No. of primitives in a leaf, and 0 for interior nodes.
+    count: usize,
+    /// The axis the children were split along, for visiting the nearer child first.
+    axis: usize,
+}
+
+/// Get a component of a point by the axis' index.
+fn axis_value(point: Point3, axis: usize) -> f64 {
+    match axis {
+        0 => point.x(),
+        1 => point.y(),
+        _ => point.z(),
+    }
+}
+
+impl Bvh {
+    pub fn bounding_box(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::EMPTY, |node| node.bbox)
+    }
+
+    /// Find the closest hit of a ray within the interval.
+    ///
+    /// `does_hit_primitive` is called with the index of every primitive whose bounds the ray passes through,
+    /// along with the interval to check in (which shrinks as closer hits are found).
+    pub fn does_hit<F>(
+        &self,
+        ray: Ray,
+        interval: Interval,
+        mut does_hit_primitive: F,
+    ) -> Option<HitData>
+    where
+        F: FnMut(usize, Interval) -> Option<HitData>,
+    {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut hit_data: Option<HitData> = None;
+        let mut closest = interval.max;
+
+        // If the ray points in the negative direction of an axis, the second child is the nearer one.
+        let is_negative = [
+            ray.direction().x() < 0.0,
+            ray.direction().y() < 0.0,
+            ray.direction().z() < 0.0,
+        ];
+
+        let mut stack = [0_usize; MAX_DEPTH];
+        let mut stack_size = 1;
+
+        while stack_size > 0 {
+            stack_size -= 1;
+            let index = stack[stack_size];
+            let node = self.nodes[index];
+
+            if !node
+                .bbox
+                .does_hit(ray, Interval::new(interval.min, closest))
+            {
+                continue;
+            }
+
+            if node.count > 0 {
+                for &primitive in &self.indices[node.offset..(node.offset + node.count)] {
+                    if let Some(hit) =
+                        does_hit_primitive(primitive, Interval::new(interval.min, closest))
+                    {
+                        closest = *hit.time();
+                        hit_data = Some(hit);
+                    }
+                }
+            } else {
+                // Push the farther child first, so the nearer one is popped (and visited) first.
+                let (near, far) = match is_negative[node.axis] {
+                    true => (node.offset, index + 1),
+                    false => (index + 1, node.offset),
+                };
+
+                stack[stack_size] = far;
+                stack[stack_size + 1] = near;
+                stack_size += 2;
+            }
+        }
+
+        hit_data
+    }
+
+    /// Recursively build the subtree for `self.indices[start..end]`, returning the index of its root node.
+    fn build(
+        &mut self,
+        bounds: &[Aabb],
+        centroids: &[Point3],
+        start: usize,
+        end: usize,
+        depth: usize,
+    ) -> usize {
+        let node_index = self.nodes.len();
+        let count = end - start;
+
+        let bbox = self.indices[start..end]
+            .iter()
+            .fold(Aabb::EMPTY, |bbox, &i| bbox.union(bounds[i]));
+
+        self.nodes.push(Node {
+            bbox,
+            offset: start,
+            count,
+            axis: 0,
+        });
+
+        if count <= 1 || depth >= MAX_DEPTH / 2 {
+            return node_index;
+        }
+
+        // The split is chosen based on where the centroids lie, not the boxes themselves.
+        let centroid_bounds = self.indices[start..end]
+            .iter()
+            .fold(Aabb::EMPTY, |bbox, &i| {
+                bbox.union(Aabb::new(centroids[i], centroids[i]))
+            });
+
+        // Surface area heuristic:
+        // The chance of a ray hitting a child is roughly proportional to its surface area,
+        // so the cost of a split is the sum of (area * no. of primitives) of both children.
+        let area = match bbox.surface_area() > 0.0 {
+            true => bbox.surface_area(),
+            false => 1.0,
+        };
+
+        // (cost, axis, bin to split at)
+        let mut best: Option<(f64, usize, usize)> = None;
+
+        for axis in 0..3 {
+            let low = axis_value(centroid_bounds.min(), axis);
+            let high = axis_value(centroid_bounds.max(), axis);
+
+            // All the centroids are at the same spot along this axis, so no split is possible.
+            if high <= low {
+                continue;
+            }
+
+            let mut bins = [(Aabb::EMPTY, 0_usize); BINS];
+
+            for &i in &self.indices[start..end] {
+                let bin = Self::bin_of(axis_value(centroids[i], axis), low, high);
+                bins[bin].0 = bins[bin].0.union(bounds[i]);
+                bins[bin].1 += 1;
+            }
+
+            // Sweep from the right first, storing the cost of everything right of each split..
+            let mut right_costs = [0.0; BINS];
+            let mut right = (Aabb::EMPTY, 0);
+
+            for split in (1..BINS).rev() {
+                right = (right.0.union(bins[split].0), right.1 + bins[split].1);
+                right_costs[split] = right.0.surface_area() * right.1 as f64;
+            }
+
+            // ..then sweep from the left and add it up.
+            let mut left = (Aabb::EMPTY, 0);
+
+            for split in 1..BINS {
+                left = (left.0.union(bins[split - 1].0), left.1 + bins[split - 1].1);
+
+                let cost = TRAVERSAL_COST
+                    + (left.0.surface_area() * left.1 as f64 + right_costs[split]) / area;
+
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, split));
+                }
+            }
+        }
+
+        let mid = match best {
+            // Split if it's cheaper than intersecting everything, or if the leaf would be too big.
+            Some((cost, axis, split)) if cost < count as f64 || count > MAX_LEAF_SIZE => {
+                let low = axis_value(centroid_bounds.min(), axis);
+                let high = axis_value(centroid_bounds.max(), axis);
+
+                self.nodes[node_index].axis = axis;
+                self.partition(start, end, |i| {
+                    Self::bin_of(axis_value(centroids[i], axis), low, high) < split
+                })
+            }
+            // All the centroids are on the same spot, just split the list in two.
+            None if count > MAX_LEAF_SIZE => start + count / 2,
+            _ => return node_index,
+        };
+
+        self.nodes[node_index].count = 0;
+
+        self.build(bounds, centroids, start, mid, depth + 1);
+        let second = self.build(bounds, centroids, mid, end, depth + 1);
+        self.nodes[node_index].offset = second;
+
+        node_index
+    }
+
+    /// Find which bin a centroid at `value` (between `low` and `high`) falls into.
+    fn bin_of(value: f64, low: f64, high: f64) -> usize {
+        (((value - low) / (high - low) * BINS as f64) as usize).min(BINS - 1)
+    }
+
+    /// Reorder `self.indices[start..end]` so everything matching the predicate comes first,
+    /// returning where the rest begins.
+    fn partition<P: Fn(usize) -> bool>(&mut self, start: usize, end: usize, predicate: P) -> usize {
+        let mut mid = start;
+
+        for i in start..end {
+            if predicate(self.indices[i]) {
+                self.indices.swap(i, mid);
+                mid += 1;
+            }
+        }
+
+        mid
+    }
+
+    /// Build a hierarchy over primitives with the given bounding boxes.
+    pub fn new(bounds: &[Aabb]) -> Self {
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(bounds.len() * 2),
+            indices: (0..bounds.len()).collect(),
+        };
+
+        let centroids: Vec<Point3> = bounds.iter().map(Aabb::centroid).collect();
+
+        if !bounds.is_empty() {
+            bvh.build(bounds, &centroids, 0, bounds.len(), 0);
+        }
+
+        bvh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        interval,
+        materials::Lambertian,
+        objects::{Object, Sphere},
+        point3,
+        structs::{Color, Vec3},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::sync::Arc;
+
+    /// Spheres of all sizes, scattered (and overlapping) in a 10 unit cube.
+    fn spheres(rng: &mut StdRng, count: usize) -> Vec<Sphere> {
+        let material = Arc::new(Lambertian::new(Color::WHITE));
+
+        (0..count)
+            .map(|_| {
+                Sphere::new(
+                    point3!(
+                        rng.gen_range(-5.0..5.0),
+                        rng.gen_range(-5.0..5.0),
+                        rng.gen_range(-5.0..5.0)
+                    ),
+                    rng.gen_range(0.05..1.0),
+                    material.clone(),
+                    Vec3::new(0, 0, 0),
+                )
+            })
+            .collect()
+    }
+
+    fn random_ray(rng: &mut StdRng) -> Ray {
+        let mut coordinate = || rng.gen_range(-8.0..8.0);
+
+        Ray::new(
+            point3!(coordinate(), coordinate(), coordinate()),
+            Vec3::new(coordinate(), coordinate(), coordinate()),
+        )
+    }
+
+    #[test]
+    fn hits_match_checking_every_primitive() {
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for count in [1, 3, 50, 500] {
+            let spheres = spheres(&mut rng, count);
+            let bounds: Vec<Aabb> = spheres
+                .iter()
+                .map(|sphere| sphere.bounding_box(interval!(0, 0)))
+                .collect();
+            let bvh = Bvh::new(&bounds);
+
+            for _ in 0..1000 {
+                let ray = random_ray(&mut rng);
+                let interval = interval!(0.001, f64::INFINITY);
+
+                let expected = spheres
+                    .iter()
+                    .filter_map(|sphere| sphere.does_hit(ray, interval, 0.0))
+                    .min_by(|a, b| a.time().total_cmp(b.time()));
+                let got = bvh.does_hit(ray, interval, |i, interval| {
+                    spheres[i].does_hit(ray, interval, 0.0)
+                });
+
+                assert_eq!(expected.map(|hit| *hit.time()), got.map(|hit| *hit.time()));
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_cover_every_primitive() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let bounds: Vec<Aabb> = spheres(&mut rng, 100)
+            .iter()
+            .map(|sphere| sphere.bounding_box(interval!(0, 0)))
+            .collect();
+        let bvh = Bvh::new(&bounds);
+
+        let union = bounds
+            .iter()
+            .fold(Aabb::EMPTY, |union, &bbox| union.union(bbox));
+        let components = |point: Point3| [point.x(), point.y(), point.z()];
+        assert_eq!(
+            components(bvh.bounding_box().min()),
+            components(union.min())
+        );
+        assert_eq!(
+            components(bvh.bounding_box().max()),
+            components(union.max())
+        );
+
+        // Every primitive ends up in exactly one leaf.
+        let mut indices = bvh.indices.clone();
+        indices.sort();
+        assert_eq!(indices, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn empty_hierarchy_is_never_hit() {
+        let bvh = Bvh::new(&[]);
+        let mut rng = StdRng::seed_from_u64(4);
+
+        assert!(bvh
+            .does_hit(random_ray(&mut rng), interval!(0, f64::INFINITY), |_, _| {
+                panic!("There are no primitives.")
+            })
+            .is_none());
+    }
+}
@@ -2,56 +2,94 @@
 
 use std::ops::{Add, AddAssign, Div, Mul};
 
+use super::Interval;
 use rand::Rng;
 
+/// A color in linear RGB, where 1.0 in a channel is "white".
+///
+/// Radiance can go past 1.0 (eg. lights brighter than white), so this is only
+/// squashed into a displayable range right before being written to a file (see `to_rgb8()`).
 #[derive(Clone, Copy, Debug)]
 pub struct Color {
-    r: i32,
-    g: i32,
-    b: i32,
+    r: f64,
+    g: f64,
+    b: f64,
 }
 
 impl Color {
     pub const WHITE: Color = Color {
-        r: 255,
-        g: 255,
-        b: 255,
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+    };
+    pub const RED: Color = Color {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+    };
+    pub const BLACK: Color = Color {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
     };
-    pub const RED: Color = Color { r: 255, g: 0, b: 0 };
-    pub const BLACK: Color = Color { r: 0, g: 0, b: 0 };
     pub const BLUE: Color = Color {
-        r: 128,
-        g: 180,
-        b: 255,
+        r: 0.5,
+        g: 0.7,
+        b: 1.0,
     };
 
-    pub fn r(&self) -> i32 {
+    pub fn r(&self) -> f64 {
         self.r
     }
 
-    pub fn g(&self) -> i32 {
+    pub fn g(&self) -> f64 {
         self.g
     }
 
-    pub fn b(&self) -> i32 {
+    pub fn b(&self) -> f64 {
         self.b
     }
 
+    pub fn is_black(&self) -> bool {
+        self.r == 0.0 && self.g == 0.0 && self.b == 0.0
+    }
+
+    /// How bright the color looks, as the Y of CIE XYZ (for the sRGB / Rec. 709 primaries).
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
     pub fn as_string(&self) -> String {
         format!("{} {} {}", self.r(), self.g(), self.b())
     }
 
+    /// Quantize the color to 8 bits per channel, clamping anything outside 0 - 1.
+    ///
+    /// This loses everything brighter than white, so it should only be done when writing the output.
+    pub fn to_rgb8(&self) -> [u8; 3] {
+        let channel = |c: f64| (256.0 * Interval::INTENSITY.clamp(c)) as u8;
+
+        [channel(self.r), channel(self.g), channel(self.b)]
+    }
+
+    /// Quantize the color to 16 bits per channel, clamping anything outside 0 - 1.
+    pub fn to_rgb16(&self) -> [u16; 3] {
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * u16::MAX as f64).round() as u16;
+
+        [channel(self.r), channel(self.g), channel(self.b)]
+    }
+
     pub fn random() -> Self {
         let mut rng = rand::thread_rng();
 
         Color {
-            r: rng.gen_range(0..256),
-            g: rng.gen_range(0..256),
-            b: rng.gen_range(0..256),
+            r: rng.gen_range(0.0..1.0),
+            g: rng.gen_range(0.0..1.0),
+            b: rng.gen_range(0.0..1.0),
         }
     }
 
-    pub fn new<X: Into<i32>, Y: Into<i32>, Z: Into<i32>>(r: X, g: Y, b: Z) -> Self {
+    pub fn new<X: Into<f64>, Y: Into<f64>, Z: Into<f64>>(r: X, g: Y, b: Z) -> Self {
         Color {
             r: r.into(),
             g: g.into(),
@@ -78,7 +116,7 @@ impl AddAssign for Color {
     }
 }
 
-impl<T: Into<i32>> Add<T> for Color {
+impl<T: Into<f64>> Add<T> for Color {
     type Output = Self;
 
     fn add(self, rhs: T) -> Self::Output {
@@ -87,7 +125,7 @@ impl<T: Into<i32>> Add<T> for Color {
     }
 }
 
-impl<T: Into<i32>> AddAssign<T> for Color {
+impl<T: Into<f64>> AddAssign<T> for Color {
     fn add_assign(&mut self, rhs: T) {
         let rhs = rhs.into();
         *self = Color {
@@ -103,11 +141,7 @@ impl<T: Into<f64>> Mul<T> for Color {
 
     fn mul(self, scalar: T) -> Self::Output {
         let scalar = scalar.into();
-        Color::new(
-            (self.r() as f64 * scalar) as i32,
-            (self.g() as f64 * scalar) as i32,
-            (self.b() as f64 * scalar) as i32,
-        )
+        Color::new(self.r() * scalar, self.g() * scalar, self.b() * scalar)
     }
 }
 
@@ -116,9 +150,9 @@ impl Mul for Color {
 
     fn mul(self, color: Color) -> Self::Output {
         Color::new(
-            (self.r() as f64 * color.r() as f64) as i32,
-            (self.g() as f64 * color.g() as f64) as i32,
-            (self.b() as f64 * color.b() as f64) as i32,
+            self.r() * color.r(),
+            self.g() * color.g(),
+            self.b() * color.b(),
         )
     }
 }
@@ -128,11 +162,7 @@ impl<T: Into<f64>> Div<T> for Color {
 
     fn div(self, scalar: T) -> Self::Output {
         let scalar = scalar.into();
-        Color::new(
-            (self.r() as f64 / scalar) as i32,
-            (self.g() as f64 / scalar) as i32,
-            (self.b() as f64 / scalar) as i32,
-        )
+        Color::new(self.r() / scalar, self.g() / scalar, self.b() / scalar)
     }
 }
 
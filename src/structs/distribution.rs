@@ -0,0 +1,211 @@
+//! Piecewise-constant probability distributions, for picking samples in proportion to some function
+//! (eg. the brightness of an environment map, so bright spots get sampled more often).
+//!
+//! https://pbr-book.org/3ed-2018/Monte_Carlo_Integration/Sampling_Random_Variables#Example:Piecewise-Constant1DFunctions
+
+/// A distribution over 0 - 1, split into equal parts with their own (relative) probabilities.
+#[derive(Debug, Clone)]
+pub struct Distribution1D {
+    /// The function being sampled, one value per part.
+    func: Vec<f64>,
+    /// The cumulative distribution, with one more entry than `func` (starting at 0 and ending at 1).
+    cdf: Vec<f64>,
+    /// The integral of `func` over 0 - 1.
+    integral: f64,
+}
+
+impl Distribution1D {
+    /// Negative values are treated as zeroes. If everything is zero, every part is equally likely.
+    pub fn new(func: Vec<f64>) -> Self {
+        let count = func.len() as f64;
+        let func: Vec<f64> = func.into_iter().map(|value| value.max(0.0)).collect();
+
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for value in &func {
+            cdf.push(cdf.last().unwrap() + value / count);
+        }
+
+        let integral = *cdf.last().unwrap();
+
+        // Normalize the running sum into a proper cdf.
+        for (i, entry) in cdf.iter_mut().enumerate() {
+            *entry = match integral > 0.0 {
+                true => *entry / integral,
+                false => i as f64 / count,
+            };
+        }
+
+        Distribution1D {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// Turn a uniformly random number (0 - 1) into a sample of the distribution.
+    ///
+    /// Returns the sample (0 - 1), its pdf, and the index of the part it is in.
+    pub fn sample(&self, random: f64) -> (f64, f64, usize) {
+        // The last entry of the cdf that isn't past `random`.
+        let index = self
+            .cdf
+            .partition_point(|&entry| entry <= random)
+            .clamp(1, self.func.len())
+            - 1;
+
+        // How far into the part the sample is.
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let offset = match width > 0.0 {
+            true => (random - self.cdf[index]) / width,
+            false => 0.0,
+        };
+
+        let sample = (index as f64 + offset) / self.func.len() as f64;
+
+        (sample, self.pdf(index), index)
+    }
+
+    /// The pdf of samples in the `index`th part.
+    pub fn pdf(&self, index: usize) -> f64 {
+        match self.integral > 0.0 {
+            true => self.func[index] / self.integral,
+            false => 1.0,
+        }
+    }
+}
+
+/// A distribution over the unit square, split into a grid of parts with their own probabilities.
+///
+/// Sampled by first picking a row (by how much each row has in total), then a part in that row.
+#[derive(Debug, Clone)]
+pub struct Distribution2D {
+    rows: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// `func` has `width` values per row, row by row.
+    pub fn new(func: &[f64], width: usize) -> Self {
+        let rows: Vec<Distribution1D> = func
+            .chunks_exact(width)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+
+        let marginal = Distribution1D::new(rows.iter().map(|row| row.integral()).collect());
+
+        Distribution2D { rows, marginal }
+    }
+
+    /// Turn two uniformly random numbers (0 - 1) into a sample `(x, y)` of the distribution, with its pdf.
+    pub fn sample(&self, random: (f64, f64)) -> ((f64, f64), f64) {
+        let (y, pdf_y, row) = self.marginal.sample(random.1);
+        let (x, pdf_x, _) = self.rows[row].sample(random.0);
+
+        ((x, y), pdf_x * pdf_y)
+    }
+
+    /// The pdf of sampling the point `(x, y)`.
+    pub fn pdf(&self, (x, y): (f64, f64)) -> f64 {
+        let row = ((y * self.rows.len() as f64) as usize).min(self.rows.len() - 1);
+        let row_distribution = &self.rows[row];
+        let column = ((x * row_distribution.func.len() as f64) as usize)
+            .min(row_distribution.func.len() - 1);
+
+        match self.marginal.integral > 0.0 {
+            true => row_distribution.func[column] / self.marginal.integral,
+            false => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// A 4 by 3 grid, with an empty row and an empty part.
+    const WIDTH: usize = 4;
+    const FUNC: [f64; 12] = [
+        1.0, 2.0, 0.0, 5.0, //
+        0.0, 0.0, 0.0, 0.0, //
+        3.0, 0.5, 0.5, 4.0,
+    ];
+
+    #[test]
+    fn samples_have_the_pdf_of_where_they_land() {
+        let distribution = Distribution2D::new(&FUNC, WIDTH);
+        let mut rng = StdRng::seed_from_u64(5);
+
+        for _ in 0..10000 {
+            let (point, pdf) =
+                distribution.sample((rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)));
+
+            assert!(pdf > 0.0);
+            assert!((0.0..=1.0).contains(&point.0) && (0.0..=1.0).contains(&point.1));
+            assert!((distribution.pdf(point) - pdf).abs() < 1e-9, "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn samples_land_in_proportion_to_the_function() {
+        let distribution = Distribution2D::new(&FUNC, WIDTH);
+        let mut rng = StdRng::seed_from_u64(6);
+        let total: f64 = FUNC.iter().sum();
+        let samples = 100000;
+
+        let mut counts = [0; 12];
+        for _ in 0..samples {
+            let ((x, y), _) =
+                distribution.sample((rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)));
+            let column = ((x * WIDTH as f64) as usize).min(WIDTH - 1);
+            let row = ((y * 3.0) as usize).min(2);
+
+            counts[row * WIDTH + column] += 1;
+        }
+
+        for (count, value) in counts.iter().zip(FUNC) {
+            let expected = value / total;
+            let got = *count as f64 / samples as f64;
+
+            match value > 0.0 {
+                true => assert!((got - expected).abs() < 0.01, "{} vs {}", got, expected),
+                false => assert_eq!(*count, 0),
+            }
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let distribution = Distribution2D::new(&FUNC, WIDTH);
+
+        // The pdf is constant over every part, which is 1/12th of the square.
+        let integral: f64 = (0..12)
+            .map(|i| {
+                let center = (
+                    ((i % WIDTH) as f64 + 0.5) / WIDTH as f64,
+                    ((i / WIDTH) as f64 + 0.5) / 3.0,
+                );
+                distribution.pdf(center) / 12.0
+            })
+            .sum();
+
+        assert!((integral - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn all_zeroes_is_uniform() {
+        let distribution = Distribution2D::new(&[0.0; 6], 3);
+
+        for random in [(0.1, 0.9), (0.5, 0.5), (0.99, 0.01)] {
+            let ((x, y), pdf) = distribution.sample(random);
+
+            assert!((x - random.0).abs() < 1e-12 && (y - random.1).abs() < 1e-12);
+            assert_eq!(pdf, 1.0);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! What rays that don't hit anything see, which also lights the scene.
+
+use super::{Color, Distribution2D, Vec3};
+use crate::{
+    point3,
+    textures::{ImageTexture, Texture},
+    vec3,
+};
+use rand::Rng;
+use std::{
+    f64::consts::{FRAC_PI_2, PI},
+    sync::Arc,
+};
+
+/// The pdf of picking a direction uniformly at random.
+const UNIFORM_SPHERE_PDF: f64 = 1.0 / (4.0 * PI);
+
+/// An image of everything around the scene (eg. a studio HDRI), mapped onto directions.
+///
+/// The image is equirectangular, with longitude going across it and latitude going up it,
+/// and the center of the image in the negative z direction (as rendered by the `Equirectangular` camera).
+#[derive(Debug, Clone)]
+pub struct EnvironmentMap {
+    image: Arc<ImageTexture>,
+    /// Rotation around the vertical (y) axis, in radians.
+    rotation: f64,
+    intensity: f64,
+    /// For picking directions in proportion to how bright the image is.
+    distribution: Arc<Distribution2D>,
+}
+
+impl EnvironmentMap {
+    /// An environment from an image (see `import::load_image()`), turned `rotation` degrees
+    /// counter-clockwise (seen from above) around the vertical axis, and multiplied by `intensity`.
+    pub fn new<R: Into<f64>, I: Into<f64>>(image: ImageTexture, rotation: R, intensity: I) -> Self {
+        let (width, height) = (image.width(), image.height());
+
+        // Every pixel's brightness, weighted by how much of the sphere it covers, which shrinks towards the poles.
+        let brightness: Vec<f64> = image
+            .pixels()
+            .iter()
+            .enumerate()
+            .map(|(i, pixel)| {
+                let latitude = (0.5 - ((i / width) as f64 + 0.5) / height as f64) * PI;
+                pixel.luminance() * latitude.cos()
+            })
+            .collect();
+
+        // Sampled with the first row at the top, unlike UVs.
+        let distribution = Distribution2D::new(&brightness, width);
+
+        EnvironmentMap {
+            image: Arc::new(image),
+            rotation: rotation.into().to_radians(),
+            intensity: intensity.into(),
+            distribution: Arc::new(distribution),
+        }
+    }
+
+    /// The image's UVs in a (unit) direction.
+    fn uv(&self, direction: Vec3) -> (f64, f64) {
+        // Turning the environment one way is the same as turning the direction the other way.
+        let longitude = direction.x().atan2(-direction.z()) + self.rotation;
+        let latitude = direction.y().clamp(-1.0, 1.0).asin();
+
+        let u = (longitude / PI + 1.0) / 2.0;
+        // The image repeats across, but not over the poles, so stay within the first and last rows.
+        let half_row = 0.5 / self.image.height() as f64;
+        let v = (latitude / FRAC_PI_2 + 1.0) / 2.0;
+
+        (u.rem_euclid(1.0), v.clamp(half_row, 1.0 - half_row))
+    }
+
+    pub fn color(&self, direction: Vec3) -> Color {
+        self.image.value(self.uv(direction), point3!(0, 0, 0)) * self.intensity
+    }
+
+    /// Pick a (unit) direction, more likely where the image is brighter, with its pdf over directions.
+    pub fn sample(&self) -> (Vec3, f64) {
+        let mut rng = rand::thread_rng();
+        let ((u, row), pdf) = self
+            .distribution
+            .sample((rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)));
+
+        let longitude = (u * 2.0 - 1.0) * PI - self.rotation;
+        let latitude = (0.5 - row) * PI;
+
+        let direction = vec3!(
+            latitude.cos() * longitude.sin(),
+            latitude.sin(),
+            -latitude.cos() * longitude.cos()
+        );
+
+        (direction, Self::to_solid_angle(pdf, latitude))
+    }
+
+    /// The pdf of `sample()` picking a (unit) direction.
+    pub fn pdf(&self, direction: Vec3) -> f64 {
+        let (u, v) = self.uv(direction);
+        let latitude = direction.y().clamp(-1.0, 1.0).asin();
+
+        Self::to_solid_angle(self.distribution.pdf((u, 1.0 - v)), latitude)
+    }
+
+    /// Turn a pdf over the image into one over directions.
+    ///
+    /// The image covers 2π of longitude and π of latitude, but rows near the poles
+    /// are squashed into a smaller part of the sphere (by the cosine of the latitude).
+    fn to_solid_angle(pdf: f64, latitude: f64) -> f64 {
+        let cos_latitude = latitude.cos();
+
+        match cos_latitude > 0.0 {
+            true => pdf / (2.0 * PI * PI * cos_latitude),
+            false => 0.0,
+        }
+    }
+}
+
+/// What a ray sees when it doesn't hit anything.
+///
+/// Besides emissive materials, this is what lights the scene, so interior scenes
+/// (lit only by lamps in the scene) should use a black `Constant`.
+#[derive(Debug, Clone)]
+pub enum Environment {
+    /// A single color in every direction.
+    Constant(Color),
+    /// A gradient from `bottom` straight down to `top` straight up.
+    Gradient {
+        bottom: Color,
+        top: Color,
+    },
+    Map(EnvironmentMap),
+}
+
+impl Environment {
+    /// A gradient from white to blue, resulting in a pretty neat sky.
+    pub const SKY: Environment = Environment::Gradient {
+        bottom: Color::WHITE,
+        top: Color::BLUE,
+    };
+
+    /// Get the color of the environment in the ray's direction.
+    pub fn color(&self, direction: Vec3) -> Color {
+        let direction = direction.unit_vec();
+
+        match self {
+            Environment::Constant(color) => *color,
+            Environment::Gradient { bottom, top } => {
+                // Linear interpolation. (fancy speak for gradient)
+                let step = (direction.y() + 1.0) * 0.5;
+
+                *bottom * (1.0 - step) + *top * step
+            }
+            Environment::Map(map) => map.color(direction),
+        }
+    }
+
+    /// Pick a (unit) direction to look for light in, with its pdf over directions.
+    ///
+    /// Maps are sampled by their brightness, other environments uniformly.
+    /// A black environment gives no light, so there's nothing to sample.
+    pub fn sample(&self) -> Option<(Vec3, f64)> {
+        match self {
+            Environment::Constant(color) if color.is_black() => None,
+            Environment::Map(map) => Some(map.sample()),
+            _ => Some((Vec3::random_unit(), UNIFORM_SPHERE_PDF)),
+        }
+    }
+
+    /// The pdf of `sample()` picking a direction.
+    pub fn pdf(&self, direction: Vec3) -> f64 {
+        match self {
+            Environment::Constant(color) if color.is_black() => 0.0,
+            Environment::Map(map) => map.pdf(direction.unit_vec()),
+            _ => UNIFORM_SPHERE_PDF,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! A structure representing the directions along and out of a surface at a point.
+
+use super::Vec3;
+use crate::vec3;
+
+/// Three perpendicular unit vectors at a point on a surface, for going between world space and the surface's
+/// local space, where the tangent is +x, the bitangent +y and the normal +z.
+///
+/// The tangent follows the surface's UVs where it has them (the way `u` increases),
+/// which is what anisotropic materials line up with (on the back of a surface, the frame is `flip()`ped).
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    tangent: Vec3,
+    bitangent: Vec3,
+    normal: Vec3,
+}
+
+impl Frame {
+    /// A frame around the (unit) `normal`, with the tangent as close to `tangent` as it can be
+    /// (any direction, as long as it's not along the normal).
+    pub fn new(normal: Vec3, tangent: Vec3) -> Self {
+        // Take out the part of the tangent along the normal.
+        let tangent = tangent - normal * tangent.dot(normal);
+
+        match tangent.length_squared() > 1e-12 {
+            true => {
+                let tangent = tangent.unit_vec();
+
+                Frame {
+                    tangent,
+                    bitangent: normal.cross(tangent),
+                    normal,
+                }
+            }
+            false => Frame::from_normal(normal),
+        }
+    }
+
+    /// Any frame around the (unit) normal, for when which way the tangent points doesn't matter.
+    pub fn from_normal(normal: Vec3) -> Self {
+        let helper = match normal.x().abs() > 0.9 {
+            true => vec3!(0, 1, 0),
+            false => vec3!(1, 0, 0),
+        };
+        let tangent = normal.cross(helper).unit_vec();
+
+        Frame {
+            tangent,
+            bitangent: normal.cross(tangent),
+            normal,
+        }
+    }
+
+    pub fn tangent(&self) -> Vec3 {
+        self.tangent
+    }
+
+    pub fn bitangent(&self) -> Vec3 {
+        self.bitangent
+    }
+
+    pub fn normal(&self) -> Vec3 {
+        self.normal
+    }
+
+    /// The same frame, with the tangent and bitangent turned `angle` radians around the normal
+    /// (counter-clockwise, looking down at the surface).
+    pub fn rotate(self, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Frame {
+            tangent: self.tangent * cos + self.bitangent * sin,
+            bitangent: self.bitangent * cos - self.tangent * sin,
+            normal: self.normal,
+        }
+    }
+
+    /// The same frame seen from the other side of the surface, with all three directions reversed,
+    /// so the tangent and bitangent still lie along the same lines on it.
+    pub fn flip(self) -> Self {
+        Frame {
+            tangent: -self.tangent,
+            bitangent: -self.bitangent,
+            normal: -self.normal,
+        }
+    }
+
+    pub fn to_local(self, v: Vec3) -> Vec3 {
+        vec3!(
+            v.dot(self.tangent),
+            v.dot(self.bitangent),
+            v.dot(self.normal)
+        )
+    }
+
+    pub fn to_world(self, v: Vec3) -> Vec3 {
+        self.tangent * v.x() + self.bitangent * v.y() + self.normal * v.z()
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Point3, Vec3};
+use super::{Frame, Point3, Vec3};
 use crate::materials::Material;
 use std::sync::Arc;
 
@@ -6,31 +6,60 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct HitData {
     point: Point3,
-    normal: Vec3,
+    /// The normal, and the directions along the surface.
+    frame: Frame,
     time: f64,
     /// Is this the side facing the camera ?
     /// This is needed for things like refraction in dielectric materials.
     is_front_face: bool,
+    /// Surface coordinates of the hit point, for objects that have them.
+    uv: (f64, f64),
+    /// The wavelength (in nanometers) the ray is carrying, once it's only carrying one.
+    wavelength: Option<f64>,
     pub material: Arc<dyn Material + Sync + Send>,
 }
 
 impl HitData {
+    /// `normal` faces the side the ray came from, and `tangent` is any direction along the surface,
+    /// usually the way `u` increases, for objects that know it.
     pub fn new(
         point: Point3,
         time: f64,
         material: Arc<dyn Material + Sync + Send>,
         is_front_face: bool,
         normal: Vec3,
+        tangent: Vec3,
     ) -> Self {
+        // The back of a surface turns the whole frame around with the normal, so the directions along it
+        // (and anything lined up with them, like `Conductor::rotation()`) stay the same on both sides.
+        let frame = match is_front_face {
+            true => Frame::new(normal, tangent),
+            false => Frame::new(-normal, tangent).flip(),
+        };
+
         HitData {
             point,
             time,
-            normal,
+            frame,
             is_front_face,
+            uv: (0.0, 0.0),
+            wavelength: None,
             material,
         }
     }
 
+    /// Attach surface coordinates to the hit.
+    pub fn with_uv(mut self, u: f64, v: f64) -> Self {
+        self.uv = (u, v);
+        self
+    }
+
+    /// Attach the wavelength of the light travelling along the ray (see `Material::is_dispersive()`).
+    pub fn with_wavelength(mut self, wavelength: f64) -> Self {
+        self.wavelength = Some(wavelength);
+        self
+    }
+
     pub fn time(&self) -> &f64 {
         &self.time
     }
@@ -44,6 +73,18 @@ impl HitData {
     }
 
     pub fn normal(&self) -> Vec3 {
-        self.normal
+        self.frame.normal()
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+
+    pub fn uv(&self) -> (f64, f64) {
+        self.uv
+    }
+
+    pub fn wavelength(&self) -> Option<f64> {
+        self.wavelength
     }
 }
@@ -1,14 +1,26 @@
+mod aabb;
+mod bvh;
 mod color;
+mod distribution;
+mod environment;
+mod frame;
 mod hit_data;
 mod interval;
 mod ray;
 mod scene;
+pub mod spectrum;
 mod vec3;
 
+pub use aabb::Aabb;
+pub use bvh::Bvh;
 pub use color::Color;
+pub use distribution::{Distribution1D, Distribution2D};
+pub use environment::{Environment, EnvironmentMap};
+pub use frame::Frame;
 pub use hit_data::HitData;
 pub use interval::Interval;
 pub use ray::Ray;
 pub use scene::Scene;
+pub use spectrum::{SampledWavelengths, Spectrum};
 pub use vec3::Point3;
 pub use vec3::Vec3;
@@ -1,18 +1,80 @@
-use super::{HitData, Interval, Ray};
-use crate::objects::Object;
+use super::{Aabb, Bvh, Environment, HitData, Interval, Ray};
+use crate::{lights::Light, objects::Object};
 
 /// A struct defining the scene.
 #[derive(Debug)]
 pub struct Scene {
     objects: Vec<Box<dyn Object + Sync + Send>>,
+    lights: Vec<Box<dyn Light + Sync + Send>>,
+    environment: Environment,
+    /// Do camera rays see the environment, or just black behind the objects ?
+    environment_visible: bool,
 }
 
 impl Scene {
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Replace what rays that don't hit anything see. (`Environment::SKY` by default)
+    pub fn set_environment(&mut self, environment: Environment) {
+        self.environment = environment;
+    }
+
+    pub fn environment_visible(&self) -> bool {
+        self.environment_visible
+    }
+
+    /// Hide (or show) the environment from rays coming straight from the camera.
+    /// A hidden environment still lights the scene and shows up in reflections,
+    /// but is black behind the objects (eg. for compositing a product shot over something else).
+    pub fn set_environment_visible(&mut self, visible: bool) {
+        self.environment_visible = visible;
+    }
+
     pub fn add(&mut self, obj: Box<dyn Object + Sync + Send>) {
         self.objects.push(obj);
     }
 
+    /// Add a light, which is sampled directly from every surface that scatters light all over.
+    pub fn add_light(&mut self, light: Box<dyn Light + Sync + Send>) {
+        self.lights.push(light);
+    }
+
+    pub fn lights(&self) -> &[Box<dyn Light + Sync + Send>] {
+        &self.lights
+    }
+
+    /// Build a `Bvh` over the objects in the scene, with bounds covering the entire `shutter` interval.
+    ///
+    /// The hierarchy refers to the objects by their index, so it is only valid for this scene,
+    /// and only until another object is added.
+    pub fn build_bvh(&self, shutter: Interval) -> Bvh {
+        let bounds: Vec<Aabb> = self
+            .objects
+            .iter()
+            .map(|obj| obj.bounding_box(shutter))
+            .collect();
+
+        Bvh::new(&bounds)
+    }
+
+    /// Check if a ray hits any object in the scene, using a `Bvh` built by `build_bvh()`.
+    pub fn does_hit_bvh(
+        &self,
+        bvh: &Bvh,
+        ray: Ray,
+        interval: Interval,
+        time: f64,
+    ) -> Option<HitData> {
+        bvh.does_hit(ray, interval, |i, interval| {
+            self.objects[i].does_hit(ray, interval, time)
+        })
+    }
+
     /// Check if a ray hits any object in the scene.
+    ///
+    /// This tests every object one by one, see `does_hit_bvh()` for the faster alternative.
     pub fn does_hit(&self, ray: Ray, interval: Interval, time: f64) -> Option<HitData> {
         let mut hit_data: Option<HitData> = None;
         let mut closest = f64::INFINITY; // The first collision should always be the closest.
@@ -33,7 +95,12 @@ impl Scene {
     }
 
     pub fn new() -> Self {
-        Scene { objects: vec![] }
+        Scene {
+            objects: vec![],
+            lights: vec![],
+            environment: Environment::SKY,
+            environment_visible: true,
+        }
     }
 }
 
@@ -42,3 +109,58 @@ impl Default for Scene {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{interval, materials::Lambertian, objects::Sphere, point3, structs::Color, vec3};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::sync::Arc;
+
+    #[test]
+    fn bvh_hits_match_checking_every_object() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let material = Arc::new(Lambertian::new(Color::WHITE));
+        let mut scene = Scene::new();
+
+        // Moving spheres, so the hierarchy has to cover them for the whole time the shutter is open.
+        for _ in 0..200 {
+            scene.add(Box::new(Sphere::new(
+                point3!(
+                    rng.gen_range(-5.0..5.0),
+                    rng.gen_range(-5.0..5.0),
+                    rng.gen_range(-5.0..5.0)
+                ),
+                rng.gen_range(0.1..0.8),
+                material.clone(),
+                vec3!(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 0),
+            )));
+        }
+
+        let bvh = scene.build_bvh(interval!(0, 1));
+
+        for _ in 0..2000 {
+            let ray = Ray::new(
+                point3!(
+                    rng.gen_range(-6.0..6.0),
+                    rng.gen_range(-6.0..6.0),
+                    rng.gen_range(-6.0..6.0)
+                ),
+                vec3!(
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0)
+                ),
+            );
+            let interval = interval!(0.01, rng.gen_range(1.0..20.0));
+            let time = rng.gen_range(0.0..1.0);
+
+            assert_eq!(
+                scene.does_hit(ray, interval, time).map(|hit| *hit.time()),
+                scene
+                    .does_hit_bvh(&bvh, ray, interval, time)
+                    .map(|hit| *hit.time())
+            );
+        }
+    }
+}
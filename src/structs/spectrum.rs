@@ -0,0 +1,428 @@
+//! Going between wavelengths of light and colors, for materials that treat each wavelength differently,
+//! and for rendering with spectra instead of RGB (see `Options::spectral`).
+//!
+//! Throughout, equal amounts of every wavelength is what's white (not the sRGB D65 white point),
+//! so white surfaces and lights stay white whether they're rendered in RGB or spectrally.
+
+use super::Color;
+use crate::color;
+use std::{ops::Mul, sync::Arc, sync::OnceLock};
+
+/// The shortest wavelength (in nanometers) that's traced, around where the eye stops seeing anything.
+pub const MIN_WAVELENGTH: f64 = 360.0;
+/// The longest wavelength (in nanometers) that's traced.
+pub const MAX_WAVELENGTH: f64 = 830.0;
+
+/// A piecewise gaussian, with a different width on either side of its peak.
+fn gaussian(x: f64, mean: f64, width_below: f64, width_above: f64) -> f64 {
+    let t = (x - mean)
+        / match x < mean {
+            true => width_below,
+            false => width_above,
+        };
+
+    (-0.5 * t * t).exp()
+}
+
+/// The CIE 1931 color matching functions, which is how much a wavelength (in nanometers)
+/// adds to each of X, Y and Z.
+///
+/// These are the analytic fits from "Simple Analytic Approximations to the CIE XYZ Color Matching Functions"
+/// (Wyman, Sloan & Shirley, 2013), instead of the tables.
+pub fn wavelength_to_xyz(wavelength: f64) -> (f64, f64, f64) {
+    let x = 1.056 * gaussian(wavelength, 599.8, 37.9, 31.0)
+        + 0.362 * gaussian(wavelength, 442.0, 16.0, 26.7)
+        - 0.065 * gaussian(wavelength, 501.1, 20.4, 26.2);
+    let y = 0.821 * gaussian(wavelength, 568.8, 46.9, 40.5)
+        + 0.286 * gaussian(wavelength, 530.9, 16.3, 31.1);
+    let z = 1.217 * gaussian(wavelength, 437.0, 11.8, 36.0)
+        + 0.681 * gaussian(wavelength, 459.0, 26.0, 13.8);
+
+    (x, y, z)
+}
+
+/// Turn CIE XYZ into linear sRGB (with its D65 white point).
+pub fn xyz_to_rgb((x, y, z): (f64, f64, f64)) -> Color {
+    color!(
+        3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+        -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+        0.0556434 * x - 0.2040259 * y + 1.0572252 * z
+    )
+}
+
+/// Integrate a function of the wavelength over all of them, 1 nm at a time.
+fn integrate<F: Fn(f64) -> (f64, f64, f64)>(function: F) -> (f64, f64, f64) {
+    let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+    let mut wavelength = MIN_WAVELENGTH + 0.5;
+
+    while wavelength < MAX_WAVELENGTH {
+        let value = function(wavelength);
+        x += value.0;
+        y += value.1;
+        z += value.2;
+        wavelength += 1.0;
+    }
+
+    (x, y, z)
+}
+
+/// The CIE XYZ of equal amounts (1 per nm) of every wavelength.
+fn white_xyz() -> (f64, f64, f64) {
+    static WHITE: OnceLock<(f64, f64, f64)> = OnceLock::new();
+
+    *WHITE.get_or_init(|| integrate(wavelength_to_xyz))
+}
+
+/// Turn CIE XYZ into linear sRGB, scaled so that the XYZ of a constant spectrum of 1 (see `white_xyz()`) is white.
+fn xyz_to_balanced_rgb(xyz: (f64, f64, f64)) -> Color {
+    let (rgb, white) = (xyz_to_rgb(xyz), xyz_to_rgb(white_xyz()));
+
+    color!(
+        rgb.r() / white.r(),
+        rgb.g() / white.g(),
+        rgb.b() / white.b()
+    )
+}
+
+/// Pick a wavelength (in nanometers) from a random number in 0 - 1, more likely where the eye is more sensitive.
+///
+/// https://pbr-book.org/4ed/Radiometry,_Spectra,_and_Color/Color#SampledWavelengthsandColorSpaces
+pub fn sample_wavelength(random: f64) -> f64 {
+    538.0 - 138.888889 * (0.85691062 - 1.82750197 * random).atanh()
+}
+
+/// The pdf of `sample_wavelength()` picking a wavelength.
+pub fn wavelength_pdf(wavelength: f64) -> f64 {
+    match (MIN_WAVELENGTH..=MAX_WAVELENGTH).contains(&wavelength) {
+        true => 0.0039398042 / (0.0072 * (wavelength - 538.0)).cosh().powi(2),
+        false => 0.0,
+    }
+}
+
+/// What the light of a path gets multiplied by when it goes from carrying every wavelength (as RGB)
+/// to just the one picked by `sample_wavelength()` (single wavelength sampling, for dispersion when rendering in RGB).
+///
+/// This is the color of the wavelength over its pdf, with the same white balance as rendering spectrally
+/// (see `SampledWavelengths::to_color()`), so the average over all wavelengths is white and the colors
+/// split up by a prism look the same either way.
+/// Single wavelengths are too saturated for sRGB, so some channels are negative, which evens out over many samples.
+pub fn wavelength_weight(wavelength: f64) -> Color {
+    let pdf = wavelength_pdf(wavelength);
+
+    match pdf > 0.0 {
+        true => xyz_to_balanced_rgb(wavelength_to_xyz(wavelength)) / pdf,
+        false => Color::BLACK,
+    }
+}
+
+/// The spectra that RGB colors are built out of by `rgb_to_spectrum()`, as values from 380 to 720 nm in 10 even steps.
+const SMITS_WHITE: [f64; 10] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const SMITS_CYAN: [f64; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const SMITS_MAGENTA: [f64; 10] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const SMITS_YELLOW: [f64; 10] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const SMITS_RED: [f64; 10] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const SMITS_GREEN: [f64; 10] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const SMITS_BLUE: [f64; 10] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+/// Turn an RGB color into a smooth spectrum, and get its value at a wavelength.
+///
+/// The color is split into white plus (at most) one secondary and one primary color, each of which
+/// has a spectrum that's as flat as possible ("An RGB-to-Spectrum Conversion for Reflectances", Smits, 1999).
+/// White gives exactly 1 everywhere, so grays stay gray.
+fn rgb_to_spectrum(rgb: Color, wavelength: f64) -> f64 {
+    // Linearly interpolate between the middles of the 10 steps, and hold the ends beyond them.
+    let position = ((wavelength - 380.0) / 34.0 - 0.5).clamp(0.0, 9.0);
+    let index = (position as usize).min(8);
+    let t = position - index as f64;
+    let at = |basis: &[f64; 10]| basis[index] * (1.0 - t) + basis[index + 1] * t;
+
+    let (r, g, b) = (rgb.r(), rgb.g(), rgb.b());
+
+    // The white part is the smallest channel, and the rest is split between the other two.
+    match (r <= g && r <= b, g <= r && g <= b) {
+        (true, _) => match g <= b {
+            true => r * at(&SMITS_WHITE) + (g - r) * at(&SMITS_CYAN) + (b - g) * at(&SMITS_BLUE),
+            false => r * at(&SMITS_WHITE) + (b - r) * at(&SMITS_CYAN) + (g - b) * at(&SMITS_GREEN),
+        },
+        (false, true) => match r <= b {
+            true => g * at(&SMITS_WHITE) + (r - g) * at(&SMITS_MAGENTA) + (b - r) * at(&SMITS_BLUE),
+            false => g * at(&SMITS_WHITE) + (b - g) * at(&SMITS_MAGENTA) + (r - b) * at(&SMITS_RED),
+        },
+        (false, false) => match r <= g {
+            true => b * at(&SMITS_WHITE) + (r - b) * at(&SMITS_YELLOW) + (g - r) * at(&SMITS_GREEN),
+            false => b * at(&SMITS_WHITE) + (g - b) * at(&SMITS_YELLOW) + (r - g) * at(&SMITS_RED),
+        },
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SpectrumKind {
+    Rgb,
+    /// The temperature in kelvin.
+    Blackbody(f64),
+    /// (wavelength, value) pairs, sorted by wavelength.
+    Tabulated(Arc<[(f64, f64)]>),
+}
+
+/// The color of light given off by something, as how much of each wavelength there is.
+///
+/// Plain RGB colors convert into one (and are turned into a smooth spectrum for spectral rendering),
+/// but lights can also have the spectrum of something hot (`blackbody()`, like the sun or a filament bulb),
+/// or a measured one (`tabulated()`, like the spiky spectra of fluorescent tubes), which only look different
+/// from their RGB color when rendered spectrally.
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    kind: SpectrumKind,
+    /// How much the spectrum is multiplied by.
+    scale: f64,
+    /// What it looks like, including the scale, for rendering in RGB.
+    color: Color,
+}
+
+impl Spectrum {
+    pub const BLACK: Spectrum = Spectrum {
+        kind: SpectrumKind::Rgb,
+        scale: 1.0,
+        color: Color::BLACK,
+    };
+
+    /// The light given off by something at a temperature (in kelvin), eg. 2700 for a warm light bulb,
+    /// 5800 for the sun, and higher for bluer light.
+    ///
+    /// This is scaled to be about as bright as white, with the lights' intensity deciding how bright it really is.
+    pub fn blackbody<T: Into<f64>>(temperature: T) -> Self {
+        let temperature = temperature.into().max(1.0);
+
+        Spectrum::normalized(SpectrumKind::Blackbody(temperature))
+    }
+
+    /// A spectrum from measurements, as (wavelength in nanometers, value) pairs in any order,
+    /// going in a straight line between them, and dropping to 0 outside of them.
+    ///
+    /// Like `blackbody()`, this is scaled to be about as bright as white.
+    pub fn tabulated(mut samples: Vec<(f64, f64)>) -> Self {
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Spectrum::normalized(SpectrumKind::Tabulated(samples.into()))
+    }
+
+    /// A spectrum with the same brightness (the Y of CIE XYZ) as a constant spectrum of 1, which is white.
+    fn normalized(kind: SpectrumKind) -> Self {
+        let unscaled = Spectrum {
+            kind,
+            scale: 1.0,
+            color: Color::BLACK,
+        };
+
+        let xyz = unscaled.xyz();
+        let scale = match xyz.1 > 0.0 {
+            true => white_xyz().1 / xyz.1,
+            false => 0.0,
+        };
+
+        // Very saturated spectra (like a single spike) have colors outside of RGB,
+        // which can only get as close as leaving out what would be negative.
+        let color = xyz_to_balanced_rgb(xyz) * scale;
+
+        Spectrum {
+            color: color!(color.r().max(0.0), color.g().max(0.0), color.b().max(0.0)),
+            scale,
+            ..unscaled
+        }
+    }
+
+    /// The CIE XYZ of the spectrum.
+    fn xyz(&self) -> (f64, f64, f64) {
+        integrate(|wavelength| {
+            let value = self.at(wavelength);
+            let (x, y, z) = wavelength_to_xyz(wavelength);
+
+            (x * value, y * value, z * value)
+        })
+    }
+
+    /// The color of the spectrum, when rendering in RGB.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// How much light of a wavelength (in nanometers) there is.
+    pub fn at(&self, wavelength: f64) -> f64 {
+        let value = match &self.kind {
+            SpectrumKind::Rgb => return rgb_to_spectrum(self.color, wavelength),
+            SpectrumKind::Blackbody(temperature) => {
+                // Planck's law, leaving out the constants in front that scaling gets rid of anyway.
+                // With the wavelength in micrometers, hc/k is 14387.769 micrometer kelvins.
+                let wavelength = wavelength / 1000.0;
+                1.0 / (wavelength.powi(5) * ((14387.769 / (wavelength * temperature)).exp() - 1.0))
+            }
+            SpectrumKind::Tabulated(samples) => {
+                let next = samples.partition_point(|(sample, _)| *sample < wavelength);
+
+                match (next.checked_sub(1).map(|i| samples[i]), samples.get(next)) {
+                    (Some((w0, v0)), Some(&(w1, v1))) => match w1 > w0 {
+                        true => v0 + (v1 - v0) * (wavelength - w0) / (w1 - w0),
+                        false => v1,
+                    },
+                    (None, Some(&(w1, v1))) if w1 == wavelength => v1,
+                    _ => 0.0,
+                }
+            }
+        };
+
+        value * self.scale
+    }
+}
+
+impl From<Color> for Spectrum {
+    fn from(color: Color) -> Self {
+        Spectrum {
+            kind: SpectrumKind::Rgb,
+            scale: 1.0,
+            color,
+        }
+    }
+}
+
+impl<T: Into<f64>> Mul<T> for Spectrum {
+    type Output = Spectrum;
+
+    fn mul(self, scalar: T) -> Self::Output {
+        let scalar = scalar.into();
+
+        Spectrum {
+            scale: self.scale * scalar,
+            color: self.color * scalar,
+            ..self
+        }
+    }
+}
+
+/// The wavelengths (in nanometers) a path carries when rendering spectrally, one for each channel of `Color`,
+/// so light along the path is a `Color` of the values at these instead of RGB.
+///
+/// They're spread out over the spectrum, so a path sees a bit of everything,
+/// with the first one (the hero wavelength) being the one that's followed through dispersive materials.
+#[derive(Debug, Clone, Copy)]
+pub struct SampledWavelengths {
+    wavelengths: [f64; 3],
+    pdfs: [f64; 3],
+}
+
+impl SampledWavelengths {
+    /// Pick the wavelengths from a random number in 0 - 1, equally far apart (in terms of `sample_wavelength()`).
+    pub fn sample(random: f64) -> Self {
+        let wavelengths = [0.0, 1.0, 2.0].map(|i| sample_wavelength((random + i / 3.0).fract()));
+
+        SampledWavelengths {
+            wavelengths,
+            pdfs: wavelengths.map(wavelength_pdf),
+        }
+    }
+
+    /// The wavelength followed when the light can only go one way (see `Material::is_dispersive()`).
+    pub fn hero(&self) -> f64 {
+        self.wavelengths[0]
+    }
+
+    /// A reflectance (like a material's albedo) at the wavelengths.
+    pub fn reflectance(&self, color: Color) -> Color {
+        let [r, g, b] = self
+            .wavelengths
+            .map(|wavelength| rgb_to_spectrum(color, wavelength));
+
+        color!(r, g, b)
+    }
+
+    /// The light given off by something, at the wavelengths.
+    pub fn radiance(&self, spectrum: &Spectrum) -> Color {
+        let [r, g, b] = self.wavelengths.map(|wavelength| spectrum.at(wavelength));
+
+        color!(r, g, b)
+    }
+
+    /// Turn light at the wavelengths into RGB, going through CIE XYZ (like the eye does).
+    pub fn to_color(self, values: Color) -> Color {
+        let values = [values.r(), values.g(), values.b()];
+        let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+
+        for ((wavelength, pdf), value) in self.wavelengths.iter().zip(self.pdfs).zip(values) {
+            if pdf == 0.0 {
+                continue;
+            }
+
+            let (x_bar, y_bar, z_bar) = wavelength_to_xyz(*wavelength);
+            let weight = value / (pdf * 3.0);
+
+            x += x_bar * weight;
+            y += y_bar * weight;
+            z += z_bar * weight;
+        }
+
+        xyz_to_balanced_rgb((x, y, z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wavelength_weight_averages_to_white() {
+        let count = 100_000;
+        let mut sum = Color::BLACK;
+
+        for i in 0..count {
+            sum += wavelength_weight(sample_wavelength((i as f64 + 0.5) / count as f64));
+        }
+
+        let average = sum / count;
+        for channel in [average.r(), average.g(), average.b()] {
+            assert!((channel - 1.0).abs() < 1e-3, "{:?}", average);
+        }
+    }
+
+    #[test]
+    fn white_round_trips_through_spectral_rendering() {
+        let count = 100_000;
+        let white = Spectrum::from(Color::WHITE);
+        let mut sum = Color::BLACK;
+
+        // A white surface lit by a white light, as a path would carry it.
+        for i in 0..count {
+            let wavelengths = SampledWavelengths::sample((i as f64 + 0.5) / count as f64);
+            let light = wavelengths.reflectance(Color::WHITE) * wavelengths.radiance(&white);
+
+            sum += wavelengths.to_color(light);
+        }
+
+        let average = sum / count;
+        for channel in [average.r(), average.g(), average.b()] {
+            assert!((channel - 1.0).abs() < 1e-2, "{:?}", average);
+        }
+    }
+
+    #[test]
+    fn sampled_wavelengths_match_their_pdf() {
+        // The pdf should integrate to 1 over the traced wavelengths.
+        let (total, _, _) = integrate(|wavelength| (wavelength_pdf(wavelength), 0.0, 0.0));
+        assert!((total - 1.0).abs() < 1e-3, "{}", total);
+
+        assert!((sample_wavelength(0.0) - MIN_WAVELENGTH).abs() < 0.1);
+        assert!((sample_wavelength(1.0) - MAX_WAVELENGTH).abs() < 0.1);
+    }
+}
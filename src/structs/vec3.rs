@@ -14,6 +14,9 @@ pub type Point3 = Vec3;
 const NEAR_ZERO_OFFSET: f64 = 1e-8; // 10^-8
 
 impl Vec3 {
+    pub const INFINITY: Vec3 = Vec3(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    pub const NEG_INFINITY: Vec3 = Vec3(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+
     pub fn x(&self) -> f64 {
         self.0
     }
@@ -38,14 +41,28 @@ impl Vec3 {
         (self.0 * rhs.0) + (self.1 * rhs.1) + (self.2 * rhs.2)
     }
 
+    /// The vector perpendicular to both, by the right hand rule.
+    ///
+    /// (The y component used to have its sign flipped, which only went unnoticed because the camera's
+    /// up vector, built with it, was also walked the wrong way, see `Camera::new()`.)
     pub fn cross(&self, rhs: Vec3) -> Vec3 {
         Vec3(
             (self.1 * rhs.2) - (self.2 * rhs.1),
-            (self.0 * rhs.2) - (self.2 * rhs.0),
+            (self.2 * rhs.0) - (self.0 * rhs.2),
             (self.0 * rhs.1) - (self.1 * rhs.0),
         )
     }
 
+    /// Component-wise minimum of two vectors.
+    pub fn min(&self, rhs: Vec3) -> Vec3 {
+        Vec3(self.0.min(rhs.0), self.1.min(rhs.1), self.2.min(rhs.2))
+    }
+
+    /// Component-wise maximum of two vectors.
+    pub fn max(&self, rhs: Vec3) -> Vec3 {
+        Vec3(self.0.max(rhs.0), self.1.max(rhs.1), self.2.max(rhs.2))
+    }
+
     /// Check if the vector is almost zero.
     ///
     /// This is used when calculating the scattered rays.
@@ -69,6 +86,18 @@ impl Vec3 {
         )
     }
 
+    /// A random unit vector, equally likely to point in any direction.
+    pub fn random_unit() -> Vec3 {
+        // Generate a vector inside a unit sphere and normalize it, thus extending it to the surface.
+        loop {
+            let p = Vec3::random(Interval::new(-1, 1));
+
+            if p.length_squared() < 1.0 && !p.near_zero() {
+                break p.unit_vec();
+            }
+        }
+    }
+
     pub fn unit_vec(&self) -> Vec3 {
         *self / self.length()
     }
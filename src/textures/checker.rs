@@ -0,0 +1,52 @@
+use super::{SolidColor, Texture};
+use crate::structs::{Color, Point3};
+use std::sync::Arc;
+
+/// A 3D checkerboard, alternating between two textures in cubes of `scale` size.
+///
+/// Since it's solid (the cubes fill space), it doesn't depend on the object's UVs,
+/// and looks the same on any object.
+#[derive(Debug)]
+pub struct Checker {
+    scale: f64,
+    even: Arc<dyn Texture + Sync + Send>,
+    odd: Arc<dyn Texture + Sync + Send>,
+}
+
+impl Checker {
+    /// A checkerboard of two colors.
+    pub fn new<T: Into<f64>>(scale: T, even: Color, odd: Color) -> Self {
+        Checker::textured(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+
+    /// A checkerboard of two textures.
+    pub fn textured<T: Into<f64>>(
+        scale: T,
+        even: Arc<dyn Texture + Sync + Send>,
+        odd: Arc<dyn Texture + Sync + Send>,
+    ) -> Self {
+        Checker {
+            scale: scale.into(),
+            even,
+            odd,
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, uv: (f64, f64), point: Point3) -> Color {
+        // The sum of the cube's indices along each axis flips between even and odd with every step.
+        let cube = (point.x() / self.scale).floor()
+            + (point.y() / self.scale).floor()
+            + (point.z() / self.scale).floor();
+
+        match cube as i64 % 2 == 0 {
+            true => self.even.value(uv, point),
+            false => self.odd.value(uv, point),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use super::Texture;
+use crate::structs::{Color, Point3};
+
+/// What an image texture does for UVs outside of 0 - 1.
+#[derive(Debug, Clone, Copy)]
+pub enum WrapMode {
+    /// Tile the image.
+    Repeat,
+    /// Stretch the pixels at the edges outwards.
+    Clamp,
+    /// Tile the image, flipping every other tile so the edges line up.
+    Mirror,
+}
+
+/// How an image texture is sampled between its pixels.
+#[derive(Debug, Clone, Copy)]
+pub enum Filter {
+    /// The closest pixel, which looks blocky when magnified.
+    Nearest,
+    /// A weighted average of the 4 closest pixels.
+    Bilinear,
+    /// A Catmull-Rom spline through the 16 closest pixels, which is smoother and sharper than bilinear.
+    Bicubic,
+}
+
+/// A texture backed by an image, mapped onto the surface with its UVs.
+///
+/// UVs go from (0, 0) at the bottom left of the image to (1, 1) at the top right.
+/// By default, the image repeats outside of that and is filtered bilinearly.
+///
+/// See `import::load_image()` for loading one from a file.
+#[derive(Debug)]
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    /// The pixels (as linear colors), row by row from the top.
+    pixels: Vec<Color>,
+    wrap: WrapMode,
+    filter: Filter,
+}
+
+/// The weights of the 4 pixels around a point `t` of the way between the middle two,
+/// for Catmull-Rom interpolation.
+fn catmull_rom_weights(t: f64) -> [f64; 4] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    [
+        (-t3 + 2.0 * t2 - t) / 2.0,
+        (3.0 * t3 - 5.0 * t2 + 2.0) / 2.0,
+        (-3.0 * t3 + 4.0 * t2 + t) / 2.0,
+        (t3 - t2) / 2.0,
+    ]
+}
+
+impl ImageTexture {
+    /// Panics if the number of pixels doesn't match the dimensions.
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "An image of {}x{} needs {} pixels.",
+            width,
+            height,
+            width * height
+        );
+
+        ImageTexture {
+            width,
+            height,
+            pixels,
+            wrap: WrapMode::Repeat,
+            filter: Filter::Bilinear,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixels (as linear colors), row by row from the top.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Bring a pixel index (which can be outside the image) into it, as per the wrap mode.
+    fn wrap_index(&self, index: i64, size: usize) -> usize {
+        let size = size as i64;
+
+        let wrapped = match self.wrap {
+            WrapMode::Repeat => index.rem_euclid(size),
+            WrapMode::Clamp => index.clamp(0, size - 1),
+            WrapMode::Mirror => {
+                // Every other tile is flipped, so it repeats every two tiles.
+                let index = index.rem_euclid(size * 2);
+                match index < size {
+                    true => index,
+                    false => size * 2 - 1 - index,
+                }
+            }
+        };
+
+        wrapped as usize
+    }
+
+    /// The pixel at (x, y), wrapping around if it's outside the image.
+    fn pixel(&self, x: i64, y: i64) -> Color {
+        let x = self.wrap_index(x, self.width);
+        let y = self.wrap_index(y, self.height);
+
+        self.pixels[y * self.width + x]
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, (u, v): (f64, f64), _: Point3) -> Color {
+        // The position in pixels, with the centers of pixels at whole numbers.
+        // Images are stored from the top, but v goes up.
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        match self.filter {
+            Filter::Nearest => self.pixel(x.round() as i64, y.round() as i64),
+            Filter::Bilinear => {
+                let top = self.pixel(x0, y0) * (1.0 - tx) + self.pixel(x0 + 1, y0) * tx;
+                let bottom = self.pixel(x0, y0 + 1) * (1.0 - tx) + self.pixel(x0 + 1, y0 + 1) * tx;
+
+                top * (1.0 - ty) + bottom * ty
+            }
+            Filter::Bicubic => {
+                let weights_x = catmull_rom_weights(tx);
+                let weights_y = catmull_rom_weights(ty);
+
+                let mut color = Color::BLACK;
+                for (j, weight_y) in weights_y.iter().enumerate() {
+                    for (i, weight_x) in weights_x.iter().enumerate() {
+                        color += self.pixel(x0 + i as i64 - 1, y0 + j as i64 - 1)
+                            * (weight_x * weight_y);
+                    }
+                }
+
+                // The spline can overshoot past the pixels' values, which would give negative colors near sharp edges.
+                Color::new(color.r().max(0.0), color.g().max(0.0), color.b().max(0.0))
+            }
+        }
+    }
+}
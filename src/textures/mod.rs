@@ -0,0 +1,20 @@
+use crate::structs::{Color, Point3};
+
+/// A trait defining a texture, giving the color of a surface at every point on it.
+///
+/// Textures can use the surface coordinates (UVs) of the hit (eg. for images wrapped around
+/// an object), or the hit point itself (eg. for solid patterns an object is "carved out" of).
+pub trait Texture: std::fmt::Debug {
+    fn value(&self, uv: (f64, f64), point: Point3) -> Color;
+}
+
+mod checker;
+mod image;
+mod noise;
+mod perlin;
+mod solid;
+
+pub use checker::Checker;
+pub use image::{Filter, ImageTexture, WrapMode};
+pub use noise::{NoisePattern, NoiseTexture};
+pub use solid::SolidColor;
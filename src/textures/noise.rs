@@ -0,0 +1,56 @@
+use super::{perlin::Perlin, Texture};
+use crate::structs::{Color, Point3};
+
+/// The number of octaves of noise added together for turbulence.
+const TURBULENCE_DEPTH: u8 = 7;
+
+/// The patterns a `NoiseTexture` can make out of Perlin noise.
+#[derive(Debug, Clone, Copy)]
+pub enum NoisePattern {
+    /// Plain, smooth noise.
+    Smooth,
+    /// Several layers of noise on top of each other, like smoke or clouds.
+    Turbulence,
+    /// Stripes along the z-axis, distorted by turbulence, like veins in marble.
+    Marble,
+}
+
+/// A solid texture made of Perlin noise, blending between black and `color`.
+#[derive(Debug)]
+pub struct NoiseTexture {
+    perlin: Perlin,
+    pattern: NoisePattern,
+    /// The frequency of the noise, higher values giving smaller details.
+    scale: f64,
+    color: Color,
+}
+
+impl NoiseTexture {
+    pub fn new<T: Into<f64>>(pattern: NoisePattern, scale: T, color: Color) -> Self {
+        NoiseTexture {
+            perlin: Perlin::new(),
+            pattern,
+            scale: scale.into(),
+            color,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _: (f64, f64), point: Point3) -> Color {
+        let scaled = point * self.scale;
+
+        let intensity = match self.pattern {
+            // Noise goes from -1 to 1, so move it into 0 to 1.
+            NoisePattern::Smooth => 0.5 * (1.0 + self.perlin.noise(scaled)),
+            NoisePattern::Turbulence => self.perlin.turbulence(scaled, TURBULENCE_DEPTH).min(1.0),
+            // Only the stripes are scaled, the turbulence just shifts them around.
+            NoisePattern::Marble => {
+                0.5 * (1.0
+                    + (scaled.z() + 10.0 * self.perlin.turbulence(point, TURBULENCE_DEPTH)).sin())
+            }
+        };
+
+        self.color * intensity
+    }
+}
@@ -0,0 +1,90 @@
+//! Perlin noise, a smooth random function over space.
+//!
+//! https://raytracing.github.io/books/RayTracingTheNextWeek.html#perlinnoise
+
+use crate::{
+    structs::{Interval, Point3, Vec3},
+    vec3,
+};
+use rand::seq::SliceRandom;
+
+/// The number of random gradients, after which the noise repeats.
+const POINT_COUNT: usize = 256;
+
+/// A random gradient at every point of an integer lattice, which the noise smoothly interpolates between.
+#[derive(Debug)]
+pub(super) struct Perlin {
+    gradients: Vec<Vec3>,
+    /// Shuffled indices for each axis, hashed together to pick a lattice point's gradient.
+    permutations: [Vec<usize>; 3],
+}
+
+impl Perlin {
+    pub(super) fn new() -> Self {
+        let mut rng = rand::thread_rng();
+
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Vec3::random(Interval::new(-1, 1)).unit_vec())
+            .collect();
+
+        let permutations = [(); 3].map(|_| {
+            let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+            permutation.shuffle(&mut rng);
+            permutation
+        });
+
+        Perlin {
+            gradients,
+            permutations,
+        }
+    }
+
+    /// The noise at a point, between -1 and 1.
+    pub(super) fn noise(&self, point: Point3) -> f64 {
+        let (x, y, z) = (point.x().floor(), point.y().floor(), point.z().floor());
+
+        // Position within the lattice cell.
+        let (u, v, w) = (point.x() - x, point.y() - y, point.z() - z);
+
+        let mut accumulated = 0.0;
+
+        // Go through each corner of the cell..
+        for (di, dj, dk) in (0..8).map(|corner| (corner & 1, (corner >> 1) & 1, (corner >> 2) & 1))
+        {
+            let gradient = self.gradients[self.permutations[0][(x as i64 + di) as usize & 255]
+                ^ self.permutations[1][(y as i64 + dj) as usize & 255]
+                ^ self.permutations[2][(z as i64 + dk) as usize & 255]];
+
+            let (di, dj, dk) = (di as f64, dj as f64, dk as f64);
+
+            // ..and weigh its gradient's contribution by how close the point is to it, with the weights
+            // eased by a hermite cubic so there are no visible seams along the cell edges.
+            let weight = |t: f64, corner: f64| {
+                let t = t * t * (3.0 - 2.0 * t);
+                corner * t + (1.0 - corner) * (1.0 - t)
+            };
+
+            accumulated += weight(u, di)
+                * weight(v, dj)
+                * weight(w, dk)
+                * gradient.dot(vec3!(u - di, v - dj, w - dk));
+        }
+
+        accumulated
+    }
+
+    /// Several octaves of noise added together, each twice the frequency and half the strength.
+    pub(super) fn turbulence(&self, point: Point3, depth: u8) -> f64 {
+        let mut accumulated = 0.0;
+        let mut point = point;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accumulated += weight * self.noise(point);
+            weight *= 0.5;
+            point *= 2.0;
+        }
+
+        accumulated.abs()
+    }
+}
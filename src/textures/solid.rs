@@ -0,0 +1,20 @@
+use super::Texture;
+use crate::structs::{Color, Point3};
+
+/// A texture that is the same color everywhere.
+#[derive(Debug)]
+pub struct SolidColor {
+    color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: Color) -> Self {
+        SolidColor { color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _: (f64, f64), _: Point3) -> Color {
+        self.color
+    }
+}